@@ -0,0 +1,9 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // protox compiles the schema in-process, so building doesn't need protoc installed
+    let descriptors = protox::compile(["proto/wordd.proto"], ["proto"])?;
+    tonic_build::configure()
+        .build_client(false)
+        .compile_fds(descriptors)?;
+    println!("cargo:rerun-if-changed=proto/wordd.proto");
+    Ok(())
+}
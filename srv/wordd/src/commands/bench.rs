@@ -0,0 +1,86 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+
+use crate::commands::language_dirs;
+use crate::services::budget::Budget;
+use crate::services::generator::{scan_linear, scan_matching_words, WordConstraints, WordFilters};
+use crate::services::language::load_language;
+
+/// Draw a rack from the tile bag, turning the last `blanks` tiles into blanks
+fn draw_rack(bag: &[char], rack_size: usize, blanks: Option<usize>) -> String {
+    let mut rng = rand::thread_rng();
+    let mut tiles: Vec<char> = bag.choose_multiple(&mut rng, rack_size).copied().collect();
+    if let Some(blanks) = blanks {
+        let letters = tiles.iter().filter(|&&c| c != '_').count();
+        tiles.retain(|&c| c != '_');
+        tiles.truncate(letters.min(rack_size.saturating_sub(blanks)));
+        tiles.resize(rack_size, '_');
+    }
+    tiles.into_iter().collect()
+}
+
+fn per_query(total: Duration, racks: usize) -> f64 {
+    total.as_secs_f64() * 1000.0 / racks.max(1) as f64
+}
+
+/// Time the linear scan against the alphagram index and the lexicon automaton on random
+/// racks from each language's tile bag, checking that all three return the same words
+pub fn run(share_dir: &str, rack_size: usize, total_tiles: usize, racks: usize, blanks: Option<usize>) -> io::Result<bool> {
+    let mut identical = true;
+
+    for lang in language_dirs(share_dir)? {
+        let language = load_language(share_dir, &lang, rack_size, total_tiles);
+        let bag: Vec<char> = language.tile_bag.iter()
+            .flat_map(|(&c, &n)| std::iter::repeat_n(c, n))
+            .collect();
+
+        let mut linear_time = Duration::ZERO;
+        let mut indexed_time = Duration::ZERO;
+        let mut automaton_time = Duration::ZERO;
+        let mut matches = 0;
+
+        for _ in 0..racks {
+            let rack = draw_rack(&bag, rack_size, blanks);
+            let constraints = WordConstraints {
+                letters: Some(&rack),
+                min_vowels: None,
+                min_consonants: None,
+                vowels: &language.vowels,
+                filters: &WordFilters::default(),
+                unicorns: &language.unicorns,
+            };
+
+            let started = Instant::now();
+            let linear = scan_linear(&language.words, &constraints, 0, &Budget::unlimited());
+            linear_time += started.elapsed();
+
+            let started = Instant::now();
+            let indexed = scan_matching_words(&language.words, &language.index, &constraints, 0, &Budget::unlimited());
+            indexed_time += started.elapsed();
+
+            let started = Instant::now();
+            let walked = language.words.rack_positions(&rack);
+            automaton_time += started.elapsed();
+            let walked: Vec<String> = walked.iter().map(|&pos| language.words.text(pos as usize).to_string()).collect();
+
+            if linear.words != indexed.words || linear.words != walked {
+                eprintln!("{}: results differ for rack '{}' ({} linear, {} indexed, {} automaton)",
+                          lang, rack, linear.words.len(), indexed.words.len(), walked.len());
+                identical = false;
+            }
+            matches += linear.words.len();
+        }
+
+        let linear_ms = per_query(linear_time, racks);
+        let indexed_ms = per_query(indexed_time, racks);
+        println!("== {}: {} racks of {}, {:.1} words/rack", lang, racks, rack_size, matches as f64 / racks.max(1) as f64);
+        println!("  linear:  {:.3} ms/query", linear_ms);
+        let automaton_ms = per_query(automaton_time, racks);
+        println!("  indexed: {:.3} ms/query ({:.0}x)", indexed_ms, linear_ms / indexed_ms.max(f64::MIN_POSITIVE));
+        println!("  automaton: {:.3} ms/query ({:.0}x)", automaton_ms, linear_ms / automaton_ms.max(f64::MIN_POSITIVE));
+    }
+
+    Ok(identical)
+}
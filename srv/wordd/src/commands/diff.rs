@@ -0,0 +1,73 @@
+use std::collections::BTreeSet;
+use std::io;
+
+use crate::commands::language_dirs;
+use crate::services::language::load_language;
+use crate::services::lexicon_diff::{diff_language, SetChange};
+
+fn join_words(words: &[String], limit: usize) -> String {
+    if limit == 0 || words.len() <= limit {
+        return words.join(", ");
+    }
+    format!("{}, ... ({} more)", words[..limit].join(", "), words.len() - limit)
+}
+
+fn print_set_change(label: &str, change: &SetChange) {
+    if change.is_empty() {
+        return;
+    }
+    let fmt = |cs: &[char]| cs.iter().collect::<String>();
+    println!("  {}: +[{}] -[{}]", label, fmt(&change.added), fmt(&change.removed));
+}
+
+/// Load every language from both share directories and print what changed.
+/// `limit` caps how many added/removed words are listed (0 lists all).
+pub fn run(old_share: &str, new_share: &str, rack_size: usize, total_tiles: usize, limit: usize) -> io::Result<()> {
+    let mut langs: BTreeSet<String> = language_dirs(old_share)?.into_iter().collect();
+    langs.extend(language_dirs(new_share)?);
+
+    for lang in langs {
+        let old = load_language(old_share, &lang, rack_size, total_tiles);
+        let new = load_language(new_share, &lang, rack_size, total_tiles);
+        let diff = diff_language(&lang, &old, &new);
+
+        if diff.is_empty() {
+            println!("== {}: unchanged ({} words)", diff.lang, diff.old_count);
+            continue;
+        }
+
+        println!("== {}: {} -> {} words ({:+})", diff.lang, diff.old_count, diff.new_count,
+                 diff.new_count as i64 - diff.old_count as i64);
+        if !diff.added.is_empty() {
+            println!("  added ({}): {}", diff.added.len(), join_words(&diff.added, limit));
+        }
+        if !diff.removed.is_empty() {
+            println!("  removed ({}): {}", diff.removed.len(), join_words(&diff.removed, limit));
+        }
+        for (len, (o, n)) in &diff.by_length {
+            println!("  length {}: {} -> {} ({:+})", len, o, n, *n as i64 - *o as i64);
+        }
+        for (letter, (o, n)) in &diff.tile_bag {
+            println!("  tiles {}: {} -> {} ({:+})", letter, o, n, *n as i64 - *o as i64);
+        }
+        print_set_change("unicorns", &diff.unicorns);
+        print_set_change("vowels", &diff.vowels);
+        print_set_change("consonants", &diff.consonants);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_words_limit() {
+        let words: Vec<String> = ["ANT", "BEE", "CAT"].iter().map(|w| w.to_string()).collect();
+        assert_eq!(join_words(&words, 0), "ANT, BEE, CAT");
+        assert_eq!(join_words(&words, 3), "ANT, BEE, CAT");
+        assert_eq!(join_words(&words, 1), "ANT, ... (2 more)");
+        assert_eq!(join_words(&[], 0), "");
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+
+use crate::services::hunspell::{self, AffixFile};
+
+/// Guess the language code from a dictionary file name (e.g. `en_US.dic` -> `en`)
+fn lang_from_dic_path(dic_path: &str) -> Option<String> {
+    let stem = Path::new(dic_path).file_stem()?.to_str()?;
+    let code = stem.split(['_', '-', '.']).next()?;
+    if code.is_empty() { None } else { Some(code.to_lowercase()) }
+}
+
+/// Expand a Hunspell .dic/.aff pair and write it as `<share-dir>/words/<lang>/lexicon.txt`.
+/// With `merge`, words already in the lexicon are kept so several dictionaries can be combined.
+pub fn run(share_dir: &str, dic_path: &str, aff_path: &str, lang: Option<&str>, merge: bool) -> io::Result<()> {
+    let lang = match lang.map(str::to_lowercase).or_else(|| lang_from_dic_path(dic_path)) {
+        Some(l) => l,
+        None => return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Cannot infer language from {}; pass --lang", dic_path),
+        )),
+    };
+
+    let aff_bytes = fs::read(aff_path)?;
+    let encoding = hunspell::detect_encoding(&aff_bytes);
+    let aff = AffixFile::parse(&hunspell::decode(&aff_bytes, encoding));
+    let dic = hunspell::decode(&fs::read(dic_path)?, encoding);
+
+    let mut words = hunspell::expand_dictionary(&dic, &aff);
+    println!("Expanded {} words from {} ({})", words.len(), dic_path, encoding.name());
+
+    let lang_dir = format!("{}/words/{}", share_dir, lang);
+    let out_path = format!("{}/lexicon.txt", lang_dir);
+    fs::create_dir_all(&lang_dir)?;
+
+    if merge {
+        match File::open(&out_path) {
+            Ok(file) => {
+                let existing: BTreeSet<String> = io::BufReader::new(file)
+                    .lines()
+                    .collect::<io::Result<Vec<_>>>()?
+                    .into_iter()
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty())
+                    .collect();
+                words.extend(existing);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut out = BufWriter::new(File::create(&out_path)?);
+    for word in &words {
+        writeln!(out, "{}", word)?;
+    }
+    out.flush()?;
+
+    println!("Wrote {} words to {}", words.len(), out_path);
+    Ok(())
+}
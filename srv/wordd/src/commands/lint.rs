@@ -0,0 +1,25 @@
+use std::io;
+
+use crate::commands::language_dirs;
+use crate::services::lint::{self, Severity};
+
+/// Lint every language in a share directory and print a report.
+/// Returns whether the share directory is free of errors.
+pub fn run(share_dir: &str, max_len: usize) -> io::Result<bool> {
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for lang in language_dirs(share_dir)? {
+        let report = lint::lint_language(share_dir, &lang, max_len);
+        for finding in &report.findings {
+            println!("{}", finding);
+        }
+        let (e, w) = (report.count(Severity::Error), report.count(Severity::Warning));
+        println!("{}: {} errors, {} warnings", report.lang, e, w);
+        errors += e;
+        warnings += w;
+    }
+
+    println!("Total: {} errors, {} warnings", errors, warnings);
+    Ok(errors == 0)
+}
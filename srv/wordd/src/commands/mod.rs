@@ -0,0 +1,18 @@
+use std::fs;
+use std::io;
+
+pub mod import_hunspell;
+pub mod lint;
+pub mod diff;
+pub mod bench;
+
+/// Language directories under `<share-dir>/words`, sorted for stable output
+pub fn language_dirs(share_dir: &str) -> io::Result<Vec<String>> {
+    let mut langs: Vec<String> = fs::read_dir(format!("{}/words", share_dir))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    langs.sort();
+    Ok(langs)
+}
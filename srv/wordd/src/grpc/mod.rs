@@ -0,0 +1,336 @@
+//! Optional gRPC listener. Serves the same `AppState` through the same service
+//! functions as the actix handlers, on its own Tokio runtime and thread.
+
+// tonic fixes the error type to `Status`, however large it is
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use log::{error, info};
+use tokio_stream::Stream;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Request, Response, Status};
+
+use crate::handlers::config::{build_config, lang_infos};
+use crate::handlers::random::{duplicate_likelihoods, random_words, solve_rack};
+use crate::handlers::validation::lookup_word;
+use crate::models::{AppState, RandQuery, SolveQuery};
+use crate::handlers::{is_internal_peer, PRIORITY_HEADER};
+use crate::services::budget::QueryError;
+use crate::services::compute::Priority;
+use crate::services::generator::WordFilters;
+use crate::utils::{select_random_from_bag, select_random_from_list};
+
+pub mod pb {
+    tonic::include_proto!("wordd.v1");
+}
+
+use pb::wordd_server::{Wordd, WorddServer};
+
+/// Words per streamed message, so large solve results don't become one huge frame
+const BATCH_SIZE: usize = 256;
+
+type BatchStream = Pin<Box<dyn Stream<Item = Result<pb::WordBatch, Status>> + Send>>;
+
+fn unsupported(lang: &str) -> Status {
+    Status::not_found(format!("Language '{}' not supported", lang))
+}
+
+fn loading(lang: &str) -> Status {
+    Status::unavailable(format!("Language '{}' is still loading; retry shortly", lang))
+}
+
+/// NOT_FOUND for an unknown language, UNAVAILABLE for one that is still loading
+fn missing(state: &AppState, lang: &str) -> Status {
+    if state.is_loading(lang) { loading(lang) } else { unsupported(lang) }
+}
+
+/// Same `x-priority` convention as the HTTP API, read from request metadata. gRPC is
+/// TCP only, so a missing peer address is not taken as a Unix socket.
+fn request_priority<T>(request: &Request<T>) -> Priority {
+    if !request.remote_addr().is_some_and(|addr| is_internal_peer(Some(addr))) {
+        return Priority::Normal;
+    }
+    request
+        .metadata()
+        .get(PRIORITY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(Priority::parse)
+        .unwrap_or(Priority::Normal)
+}
+
+fn query_status(err: QueryError, lang: &str) -> Status {
+    match err {
+        QueryError::UnsupportedLanguage => unsupported(lang),
+        QueryError::Loading => loading(lang),
+        QueryError::Overloaded => Status::resource_exhausted("Too many rack queries queued; retry shortly"),
+        QueryError::InvalidCursor(message) | QueryError::InvalidRack(message) => Status::invalid_argument(message),
+        QueryError::TimedOut => Status::deadline_exceeded("Query ran past the server's time budget"),
+        QueryError::Failed => Status::internal("Query failed"),
+    }
+}
+
+fn word_filters(filters: Option<pb::WordFilters>) -> WordFilters {
+    let filters = filters.unwrap_or_default();
+    WordFilters {
+        required: filters.required,
+        excluded: filters.excluded,
+        min_len: filters.min_len.map(|n| n as usize),
+        max_len: filters.max_len.map(|n| n as usize),
+        starts_with: filters.starts_with,
+        ends_with: filters.ends_with,
+        unicorn: Some(filters.unicorn),
+    }
+}
+
+/// Split words (and any per-word likelihoods) into streamed batches; the last
+/// (possibly empty) one carries the trailer
+fn batches(words: Vec<String>, likelihoods: Vec<f32>, truncated: bool, next_cursor: Option<String>) -> BatchStream {
+    let mut batches: Vec<pb::WordBatch> = words
+        .chunks(BATCH_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| pb::WordBatch {
+            words: chunk.to_vec(),
+            duplicate_likelihood: likelihoods.iter().skip(i * BATCH_SIZE).take(chunk.len()).copied().collect(),
+            ..Default::default()
+        })
+        .collect();
+    if batches.is_empty() {
+        batches.push(pb::WordBatch::default());
+    }
+    if let Some(last) = batches.last_mut() {
+        last.truncated = truncated;
+        last.next_cursor = next_cursor;
+    }
+    Box::pin(tokio_stream::iter(batches.into_iter().map(Ok)))
+}
+
+fn char_counts(map: &HashMap<char, usize>) -> HashMap<String, u32> {
+    map.iter().map(|(c, &n)| (c.to_string(), n as u32)).collect()
+}
+
+pub struct WorddService {
+    state: Arc<AppState>,
+}
+
+#[tonic::async_trait]
+impl Wordd for WorddService {
+    async fn list_languages(
+        &self,
+        _request: Request<pb::ListLanguagesRequest>,
+    ) -> Result<Response<pb::ListLanguagesResponse>, Status> {
+        let languages = lang_infos(&self.state)
+            .into_iter()
+            .map(|info| pb::Language {
+                code: info.code,
+                name: info.name,
+                lexicon_hash: info.lexicon_hash,
+                status: info.status.as_str().to_string(),
+            })
+            .collect();
+        Ok(Response::new(pb::ListLanguagesResponse { languages }))
+    }
+
+    async fn get_config(
+        &self,
+        request: Request<pb::GetConfigRequest>,
+    ) -> Result<Response<pb::GetConfigResponse>, Status> {
+        let lang = request.into_inner().language.to_lowercase();
+        let language = self.state.language(&lang).ok_or_else(|| missing(&self.state, &lang))?;
+        let config = build_config(&lang, &language);
+        Ok(Response::new(pb::GetConfigResponse {
+            tiles: char_counts(&config.tiles),
+            unicorns: char_counts(&config.unicorns),
+            vowels: config.vowels.iter().map(|c| c.to_string()).collect(),
+            word_count: config.word_count as u64,
+            lexicon_hash: config.lexicon_hash,
+        }))
+    }
+
+    async fn validate(
+        &self,
+        request: Request<pb::ValidateRequest>,
+    ) -> Result<Response<pb::ValidateResponse>, Status> {
+        let req = request.into_inner();
+        let lang = req.language.to_lowercase();
+        let language = self.state.language(&lang).ok_or_else(|| missing(&self.state, &lang))?;
+        let valid = lookup_word(&self.state, &language, &lang, &req.word);
+        Ok(Response::new(pb::ValidateResponse {
+            lexicon_hash: language.hash.clone(),
+            language: lang,
+            word: req.word.to_uppercase(),
+            valid,
+        }))
+    }
+
+    type SolveStream = BatchStream;
+
+    async fn solve(
+        &self,
+        request: Request<pb::SolveRequest>,
+    ) -> Result<Response<Self::SolveStream>, Status> {
+        let priority = request_priority(&request);
+        let req = request.into_inner();
+        let lang = req.language.to_lowercase();
+        if req.letters.is_empty() {
+            return Err(Status::invalid_argument("letters must not be empty"));
+        }
+        let query = SolveQuery {
+            letters: req.letters,
+            limit: (req.limit > 0).then_some(req.limit as usize),
+            cursor: req.cursor,
+            likelihood: Some(req.likelihood),
+        };
+        let page = solve_rack(&self.state, &lang, &query, &word_filters(req.filters), priority)
+            .await
+            .map_err(|e| query_status(e, &lang))?;
+        let truncated = page.next_cursor.is_some();
+        let likelihoods = if req.likelihood {
+            duplicate_likelihoods(&page.language, &query.letters, &page.words)
+        } else {
+            Vec::new()
+        };
+        Ok(Response::new(batches(page.words, likelihoods, truncated, page.next_cursor)))
+    }
+
+    async fn random_letters(
+        &self,
+        request: Request<pb::RandomLettersRequest>,
+    ) -> Result<Response<pb::RandomLettersResponse>, Status> {
+        let req = request.into_inner();
+        let lang = req.language.to_lowercase();
+        let count = (req.count.unwrap_or(1) as usize).min(self.state.query_limits.max_results);
+        let language = self.state.language(&lang).ok_or_else(|| missing(&self.state, &lang))?;
+
+        let letters = match pb::LetterKind::try_from(req.kind) {
+            Ok(pb::LetterKind::Letter) => select_random_from_bag(&language.tile_bag, count),
+            Ok(pb::LetterKind::Vowel) => select_random_from_list(&language.vowels, count),
+            Ok(pb::LetterKind::Consonant) => select_random_from_list(&language.consonants, count),
+            Ok(pb::LetterKind::Unicorn) => select_random_from_list(&language.unicorns, count),
+            Ok(pb::LetterKind::Unspecified) | Err(_) => {
+                return Err(Status::invalid_argument("kind must be LETTER, VOWEL, CONSONANT, or UNICORN"));
+            }
+        };
+
+        Ok(Response::new(pb::RandomLettersResponse {
+            letters: letters.iter().map(|c| c.to_string()).collect(),
+        }))
+    }
+
+    type RandomWordsStream = BatchStream;
+
+    async fn random_words(
+        &self,
+        request: Request<pb::RandomWordsRequest>,
+    ) -> Result<Response<Self::RandomWordsStream>, Status> {
+        let priority = request_priority(&request);
+        let req = request.into_inner();
+        let lang = req.language.to_lowercase();
+        let query = RandQuery {
+            count: req.count.map(|n| n as usize),
+            letters: req.letters,
+            min_vowels: req.min_vowels.map(|n| n as usize),
+            min_consonants: req.min_consonants.map(|n| n as usize),
+            timeout_ms: req.timeout_ms,
+        };
+        let language = self.state.language(&lang).ok_or_else(|| missing(&self.state, &lang))?;
+        let (words, truncated) = random_words(&self.state, &lang, &language, &query, &word_filters(req.filters), priority)
+            .await
+            .map_err(|e| query_status(e, &lang))?;
+        Ok(Response::new(batches(words, Vec::new(), truncated, None)))
+    }
+}
+
+/// Start the gRPC listener on a dedicated thread so it doesn't share actix's workers.
+/// The address is bound before returning, so a bad or busy address fails startup.
+pub fn spawn(addr: SocketAddr, state: Arc<AppState>) -> io::Result<SocketAddr> {
+    let listener = std::net::TcpListener::bind(addr)?;
+    listener.set_nonblocking(true)?;
+    let local = listener.local_addr()?;
+
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+    let incoming = {
+        let _guard = runtime.enter();
+        TcpIncoming::from_listener(tokio::net::TcpListener::from_std(listener)?, false, None).map_err(io::Error::other)?
+    };
+    std::thread::Builder::new().name("wordd-grpc".to_string()).spawn(move || {
+        runtime.block_on(async move {
+            let service = WorddServer::new(WorddService { state });
+            if let Err(e) = tonic::transport::Server::builder().add_service(service).serve_with_incoming(incoming).await {
+                error!("gRPC server failed: {}", e);
+            }
+        });
+    })?;
+    info!("gRPC listening on {}", local);
+    Ok(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_stream::StreamExt;
+    use crate::services::language::test_language;
+
+    fn service() -> WorddService {
+        WorddService { state: Arc::new(AppState::for_tests(vec![("en", test_language(&["ACT", "CAT", "AT", "DOG"]))])) }
+    }
+
+    async fn collect(stream: BatchStream) -> Vec<pb::WordBatch> {
+        stream.map(Result::unwrap).collect().await
+    }
+
+    #[tokio::test]
+    async fn test_batches() {
+        let words: Vec<String> = (0..600).map(|i| i.to_string()).collect();
+        let likelihoods: Vec<f32> = (0..600).map(|i| i as f32).collect();
+        let sent = collect(batches(words, likelihoods, true, Some("next".to_string()))).await;
+        assert_eq!(sent.iter().map(|b| b.words.len()).collect::<Vec<_>>(), [256, 256, 88]);
+        assert_eq!(sent[1].words[0], "256");
+        assert_eq!(sent[1].duplicate_likelihood[0], 256.0);
+        assert!(!sent[0].truncated && sent[0].next_cursor.is_none());
+        assert!(sent[2].truncated && sent[2].next_cursor.as_deref() == Some("next"));
+
+        // An empty result still sends the trailer
+        let sent = collect(batches(Vec::new(), Vec::new(), true, None)).await;
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].words.is_empty() && sent[0].truncated);
+    }
+
+    #[tokio::test]
+    async fn test_solve() {
+        let request = pb::SolveRequest { language: "EN".to_string(), letters: "TCA".to_string(), ..Default::default() };
+        let sent = collect(service().solve(Request::new(request)).await.unwrap().into_inner()).await;
+        let mut words: Vec<String> = sent.into_iter().flat_map(|b| b.words).collect();
+        words.sort();
+        assert_eq!(words, ["ACT", "AT", "CAT"]);
+
+        let request = pb::SolveRequest { language: "xx".to_string(), letters: "TCA".to_string(), ..Default::default() };
+        assert_eq!(service().solve(Request::new(request)).await.err().unwrap().code(), tonic::Code::NotFound);
+        let request = pb::SolveRequest { language: "en".to_string(), ..Default::default() };
+        assert_eq!(service().solve(Request::new(request)).await.err().unwrap().code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_random_letter_counts_are_capped() {
+        let request = pb::RandomLettersRequest {
+            language: "en".to_string(),
+            kind: pb::LetterKind::Vowel as i32,
+            count: Some(u32::MAX),
+        };
+        let sent = service().random_letters(Request::new(request)).await.unwrap().into_inner();
+        assert_eq!(sent.letters.len(), 1000);
+    }
+
+    #[test]
+    fn test_spawn_binds_before_returning() {
+        let state = Arc::new(AppState::for_tests(Vec::new()));
+        let bound = spawn("127.0.0.1:0".parse().unwrap(), Arc::clone(&state)).unwrap();
+        assert_ne!(bound.port(), 0);
+        let err = spawn(bound, state).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    }
+}
@@ -0,0 +1,110 @@
+//! Routes served only on the admin listener: reloads and debugging endpoints
+//! that must never be reachable through the public service. Its metrics are the
+//! memory, cache, and rejection stats; wordd has no word-proposal workflow, so
+//! there is nothing to approve here. Without `--admin-listen` none of these routes,
+//! the rejections report and word explanations included, are served at all.
+
+use actix_web::{get, post, web, HttpResponse, Responder};
+use log::{error, info};
+
+use crate::handlers::{explain, openapi, stats};
+use crate::models::{AppState, ReloadResponse};
+use crate::services::language;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(stats::get_memory)
+        .service(stats::get_rejections)
+        .service(explain::explain_word)
+        .service(reload_language)
+        .service(cache_stats)
+        .service(openapi::get_admin_openapi);
+}
+
+#[utoipa::path(tag = "admin",
+    params(("lang" = String, Path, description = "Language code, e.g. en")),
+    responses(
+        (status = 200, description = "Language reloaded from the share directory", body = ReloadResponse),
+        (status = 404, description = "Language not configured", body = String, content_type = "text/plain"),
+    ))]
+#[post("/reload/{lang}")]
+pub async fn reload_language(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let lang = path.into_inner().to_lowercase();
+    if !data.supported_langs.contains(&lang) {
+        return HttpResponse::NotFound().body(format!("Language '{}' not configured", lang));
+    }
+
+    let previous_hash = data.loaded(&lang).map(|l| l.hash.clone());
+
+    // Loading reads and filters the whole lexicon, so keep it off the worker thread
+    let state = data.clone();
+    let load_lang = lang.clone();
+    let loaded = web::block(move || {
+        language::load_language(&state.share_dir, &load_lang, state.max_word_len, state.total_tiles)
+    }).await;
+
+    let loaded = match loaded {
+        Ok(l) => l,
+        Err(e) => {
+            error!("Reload of {} failed: {}", lang, e);
+            return HttpResponse::InternalServerError().body(format!("Reload failed: {}", e));
+        }
+    };
+
+    let resp = ReloadResponse {
+        language: lang.clone(),
+        lexicon_hash: loaded.hash.clone(),
+        previous_hash,
+        word_count: loaded.words.len(),
+    };
+    data.replace_language(&lang, loaded);
+    info!("Reloaded {}: {} words, hash {}", lang, resp.word_count, resp.lexicon_hash);
+
+    HttpResponse::Ok().json(resp)
+}
+
+#[utoipa::path(tag = "admin",
+    responses(
+        (status = 200, description = "Rack result cache size and hit/miss counters", body = crate::services::cache::CacheStats),
+    ))]
+#[get("/cache")]
+pub async fn cache_stats(data: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(data.rack_cache.stats())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::{test, web, App};
+    use utoipa::OpenApi;
+
+    use crate::handlers;
+    use crate::handlers::openapi::{AdminApiDoc, ApiDoc};
+    use crate::models::AppState;
+    use crate::services::language::test_language;
+
+    #[actix_web::test]
+    async fn test_admin_routes_stay_off_the_public_listener() {
+        let data = web::Data::new(AppState::for_tests(vec![("en", test_language(&["CAT"]))]));
+        let public = test::init_service(App::new().app_data(data.clone()).configure(handlers::configure)).await;
+        let admin = test::init_service(App::new().app_data(data.clone()).configure(super::configure)).await;
+
+        for uri in ["/stats/memory", "/stats/en/rejections", "/cache", "/explain/en/CAT"] {
+            let resp = test::call_service(&public, test::TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{} is public", uri);
+            let resp = test::call_service(&admin, test::TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(resp.status(), StatusCode::OK, "{} is missing from admin", uri);
+        }
+        let resp = test::call_service(&public, test::TestRequest::post().uri("/reload/en").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = test::call_service(&admin, test::TestRequest::get().uri("/config/en").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let public_paths = ApiDoc::openapi().paths.paths;
+        for path in AdminApiDoc::openapi().paths.paths.keys().filter(|p| *p != "/openapi.json") {
+            assert!(!public_paths.contains_key(path), "{} is in the public spec", path);
+        }
+    }
+}
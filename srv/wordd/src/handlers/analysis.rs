@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use actix_web::{post, web, HttpRequest, HttpResponse};
+
+use crate::handlers::random::{cached_matches, require_language};
+use crate::handlers::v2::error::ApiError;
+use crate::handlers::{request_priority, with_lexicon_hash};
+use crate::models::{AppState, RoundAnalysisRequest};
+use crate::services::cache::RackKey;
+use crate::services::generator::WordFilters;
+use crate::services::round_analysis::analyze_round;
+use crate::services::scoring::default_values;
+
+#[utoipa::path(tag = "rack",
+    params(("lang" = String, Path, description = "Language code, e.g. en")),
+    request_body = RoundAnalysisRequest,
+    responses(
+        (status = 200, description = "Each play against the best possible, plus the round's top and full-rack words", body = crate::services::round_analysis::RoundAnalysis),
+        (status = 400, description = "Malformed request body or a rack past the length cap", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+        (status = 503, description = "Compute queue full, query timed out, or language still loading", body = ApiError),
+    ))]
+#[post("/analysis/{lang}")]
+pub async fn analyze_round_plays(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<RoundAnalysisRequest>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let lang = path.into_inner().to_lowercase();
+    let body = body.into_inner();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let values = match body.values {
+        Some(values) => values.into_iter().map(|(c, v)| (c.to_uppercase().next().unwrap_or(c), v)).collect(),
+        None => default_values(&language.tile_bag, &language.unicorns),
+    };
+    let top = body.top.unwrap_or(10).min(data.query_limits.max_results);
+    let plays: Vec<(String, String)> = body.plays.into_iter().map(|p| (p.player, p.word)).collect();
+
+    let priority = request_priority(&req);
+    let key = RackKey::new(&lang, &language.hash, &body.rack, None, None, &WordFilters::default());
+    let matches = cached_matches(&data, &language, key, priority)
+        .await
+        .map_err(|e| ApiError::from_query(e, &lang))?;
+
+    let (analysis_lang, analyzed) = (lang.clone(), Arc::clone(&language));
+    let rack = body.rack;
+    let report = data.compute.run(priority, move || {
+        analyze_round(&analysis_lang, &rack, &analyzed.words, &matches, &values, &plays, top)
+    }).await.map_err(|e| ApiError::from_query(e, &lang))?;
+
+    Ok(with_lexicon_hash(&language, HttpResponse::Ok().json(report)))
+}
@@ -1,11 +1,13 @@
-use actix_web::{get, web, HttpResponse, Responder};
-use crate::models::{AppState, LangInfo, ConfigResponse};
+use actix_web::{get, web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use actix_web::http::header::{ETag, EntityTag, IfNoneMatch};
+use crate::models::{AppState, LangInfo, LangStatus, LanguageData, ConfigResponse};
 use std::collections::HashMap;
 use log::info;
+use crate::handlers::missing_language;
 
-#[get("/langs")]
-pub async fn get_langs(data: web::Data<AppState>) -> impl Responder {
-    let langs: Vec<LangInfo> = data.supported_langs.iter().map(|code| {
+/// Display info for every supported language, in configured order
+pub fn lang_infos(data: &AppState) -> Vec<LangInfo> {
+    data.supported_langs.iter().map(|code| {
         let name = match code.as_str() {
             "en" => "English",
             "es" => "Español",
@@ -13,49 +15,125 @@ pub async fn get_langs(data: web::Data<AppState>) -> impl Responder {
             "de" => "Deutsch",
             _ => code.as_str(),
         }.to_string();
-        LangInfo { name, code: code.clone() }
-    }).collect();
+        let (lexicon_hash, status) = match data.loaded(code) {
+            Some(l) => (l.hash.clone(), LangStatus::Ready),
+            None => (String::new(), LangStatus::Loading),
+        };
+        LangInfo { name, code: code.clone(), lexicon_hash, status }
+    }).collect()
+}
 
-    HttpResponse::Ok().json(langs)
+#[utoipa::path(tag = "config",
+    responses((status = 200, description = "Supported languages, each ready or still loading", body = Vec<LangInfo>)))]
+#[get("/langs")]
+pub async fn get_langs(data: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(lang_infos(&data))
 }
 
-#[get("/config/{lang}")]
-pub async fn get_config(
-    data: web::Data<AppState>,
-    path: web::Path<String>,
-) -> impl Responder {
-    let lang = path.into_inner().to_lowercase();
-    
-    // Retrieve pre-computed values from AppState
-    let bag = match data.tile_bags.get(&lang) {
-        Some(b) => b.clone(),
-        None => return HttpResponse::BadRequest().finish(),
-    };
+/// True if the request's If-None-Match already names the current entity tag
+pub fn is_not_modified(req: &HttpRequest, etag: &EntityTag) -> bool {
+    match req.get_header::<IfNoneMatch>() {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|t| t.weak_eq(etag)),
+        None => false,
+    }
+}
 
-    let vowels = match data.vowel_sets.get(&lang) {
-        Some(v) => v.clone(),
-        None => return HttpResponse::BadRequest().finish(),
-    };
+/// The config is fully determined by the lexicon hash, so it doubles as the ETag
+pub fn config_etag(language: &LanguageData) -> EntityTag {
+    EntityTag::new_strong(language.hash.clone())
+}
 
-    let unicorns = match data.unicorn_sets.get(&lang) {
-        Some(u) => {
-            // Convert unicorn letters to HashMap with standard value of 10
-            u.iter().map(|&c| (c, 10)).collect::<HashMap<char, usize>>()
-        },
-        None => return HttpResponse::BadRequest().finish(),
-    };
+/// Assemble the game config for a language from its pre-computed values
+pub fn build_config(lang: &str, language: &LanguageData) -> ConfigResponse {
+    let bag = language.tile_bag.clone();
+    let vowels = language.vowels.clone();
+
+    // Convert unicorn letters to HashMap with standard value of 10
+    let unicorns = language.unicorns
+        .iter()
+        .map(|&c| (c, 10))
+        .collect::<HashMap<char, usize>>();
 
-    let word_count = data.word_lists.get(&lang).map(|w| w.len()).unwrap_or(0);
+    let word_count = language.words.len();
+    let lexicon_hash = language.hash.clone();
     let tiles = bag.clone();
 
     info!("Generated {} config with {} tiles, {} unicorns, {} vowels, and {} words", 
           lang, tiles.values().sum::<usize>(), unicorns.len(), vowels.len(), word_count);
 
-    HttpResponse::Ok().json(ConfigResponse {
+    ConfigResponse {
         tiles,
         unicorns,
         vowels,
         bag,
         word_count,
-    })
+        lexicon_hash,
+    }
+}
+
+#[utoipa::path(tag = "config",
+    params(("lang" = String, Path, description = "Language code, e.g. en")),
+    responses(
+        (status = 200, description = "Tile bag and letter classes; the ETag is the lexicon hash", body = ConfigResponse),
+        (status = 304, description = "If-None-Match matched the current lexicon hash"),
+        (status = 400, description = "Language not supported"),
+    ))]
+#[get("/config/{lang}")]
+pub async fn get_config(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let lang = path.into_inner().to_lowercase();
+
+    let language = match data.language(&lang) {
+        Some(language) => language,
+        None if data.is_loading(&lang) => return missing_language(&data, &lang),
+        None => return HttpResponse::BadRequest().finish(),
+    };
+
+    // The tag and the body come from the same snapshot, so a reload can't pair them up wrong
+    let etag = config_etag(&language);
+    if is_not_modified(&req, &etag) {
+        return HttpResponse::NotModified().insert_header(ETag(etag)).finish();
+    }
+    HttpResponse::Ok().insert_header(ETag(etag)).json(build_config(&lang, &language))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::{header, StatusCode};
+    use actix_web::{test, App};
+    use crate::services::language::test_language;
+
+    #[actix_web::test]
+    async fn test_config_not_modified() {
+        let state = AppState::for_tests(vec![("en", test_language(&["CAT", "DOG"]))]);
+        let hash = state.loaded("en").unwrap().hash.clone();
+        let app = test::init_service(App::new().app_data(web::Data::new(state)).service(get_config)).await;
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/config/en").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(header::ETAG).unwrap(), &format!("\"{}\"", hash));
+        let config: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(config["lexicon_hash"], hash.as_str());
+
+        let req = test::TestRequest::get()
+            .uri("/config/en")
+            .insert_header((header::IF_NONE_MATCH, format!("\"{}\"", hash)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        let req = test::TestRequest::get()
+            .uri("/config/en")
+            .insert_header((header::IF_NONE_MATCH, "\"stale\""))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/config/xx").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
 }
@@ -0,0 +1,27 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use crate::handlers::missing_language;
+use crate::models::AppState;
+use crate::services::word_loader;
+
+#[utoipa::path(tag = "diagnostics",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), ("word" = String, Path, description = "Word to look up (case-insensitive)")),
+    responses(
+        (status = 200, description = "Trace of the word through the load pipeline", body = word_loader::WordTrace),
+        (status = 400, description = "Language not supported", body = String, content_type = "text/plain"),
+    ))]
+#[get("/explain/{lang}/{word}")]
+pub async fn explain_word(
+    data: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (lang, word) = path.into_inner();
+    let lang = lang.to_lowercase();
+
+    let language = match data.language(&lang) {
+        Some(l) => l,
+        None => return missing_language(&data, &lang),
+    };
+
+    let trace = word_loader::explain_word(data.max_word_len, &word, &language.inputs);
+    HttpResponse::Ok().json(trace)
+}
@@ -0,0 +1,96 @@
+//! Word ladder solo mode: change one letter per step to get from one word to another.
+//! Failures use the v2 error envelope.
+
+use std::sync::Arc;
+
+use actix_web::{get, web, HttpRequest, HttpResponse};
+
+use crate::handlers::random::require_language;
+use crate::handlers::v2::error::{ApiError, ErrorCode};
+use crate::handlers::{request_priority, with_lexicon_hash};
+use crate::models::{AppState, LadderPuzzleQuery, LadderQuery};
+use crate::services::ladder::{LadderPath, LadderPuzzle};
+
+#[utoipa::path(tag = "ladder",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), LadderQuery),
+    responses(
+        (status = 200, description = "A shortest ladder between the words, if one exists", body = LadderPath),
+        (status = 400, description = "Words of different lengths or not in the lexicon", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+        (status = 503, description = "Compute queue full or language still loading", body = ApiError),
+    ))]
+#[get("/ladder/{lang}")]
+pub async fn ladder_path(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<LadderQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let lang = path.into_inner().to_lowercase();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let (from, to) = (query.from.to_uppercase(), query.to.to_uppercase());
+    if from.chars().count() != to.chars().count() {
+        return Err(ApiError::new(ErrorCode::InvalidParameter, format!("'{}' and '{}' differ in length", from, to), Some(&lang)));
+    }
+    if let Some(word) = [&from, &to].into_iter().find(|w| !language.words.contains(w)) {
+        return Err(ApiError::new(ErrorCode::InvalidParameter, format!("'{}' is not a valid word", word), Some(&lang)));
+    }
+
+    let (ends, found_lang, searched) = ((from.clone(), to.clone()), lang.clone(), Arc::clone(&language));
+    let found = data.compute.run(request_priority(&req), move || {
+        searched.ladders.shortest_path(&searched.words, &ends.0, &ends.1).map(|positions| {
+            positions.into_iter().map(|pos| searched.words.text(pos as usize).to_string()).collect::<Vec<_>>()
+        })
+    }).await.map_err(|e| ApiError::from_query(e, &found_lang))?;
+
+    let resp = HttpResponse::Ok().json(LadderPath {
+        language: lang.clone(),
+        from,
+        to,
+        steps: found.as_ref().map(|path| path.len() - 1),
+        path: found,
+    });
+    Ok(with_lexicon_hash(&language, resp))
+}
+
+#[utoipa::path(tag = "ladder",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), LadderPuzzleQuery),
+    responses(
+        (status = 200, description = "A random start and target whose shortest ladder fits the difficulty", body = LadderPuzzle),
+        (status = 404, description = "Language not supported, or no ladder of that length and difficulty", body = ApiError),
+        (status = 503, description = "Compute queue full or language still loading", body = ApiError),
+    ))]
+#[get("/ladder/{lang}/puzzle")]
+pub async fn ladder_puzzle(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<LadderPuzzleQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let lang = path.into_inner().to_lowercase();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let length = query.length.unwrap_or(4);
+    let difficulty = query.difficulty.unwrap_or_default();
+
+    let searched = Arc::clone(&language);
+    let solution = data.compute.run(request_priority(&req), move || {
+        searched.ladders.puzzle(length, difficulty, &mut rand::thread_rng()).map(|positions| {
+            positions.into_iter().map(|pos| searched.words.text(pos as usize).to_string()).collect::<Vec<_>>()
+        })
+    }).await.map_err(|e| ApiError::from_query(e, &lang))?;
+    let solution = solution.ok_or_else(|| ApiError::new(
+        ErrorCode::NotFound,
+        format!("No {:?} ladder of {}-letter words found", difficulty, length).to_lowercase(),
+        Some(&lang),
+    ))?;
+
+    let resp = HttpResponse::Ok().json(LadderPuzzle {
+        language: lang.clone(),
+        difficulty,
+        start: solution[0].clone(),
+        target: solution[solution.len() - 1].clone(),
+        steps: solution.len() - 1,
+        solution,
+    });
+    Ok(with_lexicon_hash(&language, resp))
+}
@@ -1,3 +1,159 @@
+use std::net::SocketAddr;
+
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::{web, HttpRequest, HttpResponse, Scope};
+use crate::models::{AppState, LanguageData};
+use crate::services::compute::Priority;
+
+pub mod admin;
+pub mod analysis;
 pub mod config;
 pub mod validation;
 pub mod random;
+pub mod stats;
+pub mod explain;
+pub mod ladder;
+pub mod openapi;
+pub mod rack;
+pub mod v2;
+pub mod wordle;
+
+/// Response header carrying the content hash of the language that served the request
+pub const LEXICON_HASH_HEADER: &str = "x-lexicon-hash";
+
+/// Request header selecting the compute priority class: `high`, `normal` (default), or `low`.
+/// Only internal callers may choose; see `is_internal_peer`.
+pub const PRIORITY_HEADER: &str = "x-priority";
+
+/// Whether a caller is inside the deployment rather than a public client: it came in
+/// over a Unix socket (which has no peer address) or from loopback, as a sidecar does
+pub fn is_internal_peer(peer: Option<SocketAddr>) -> bool {
+    peer.is_none_or(|addr| addr.ip().is_loopback())
+}
+
+/// The compute priority an internal caller asked for; everyone else gets `Normal`,
+/// so a public client can't push its queries ahead of the game backend's
+pub fn request_priority(req: &HttpRequest) -> Priority {
+    if !is_internal_peer(req.peer_addr()) {
+        return Priority::Normal;
+    }
+    req.headers()
+        .get(PRIORITY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(Priority::parse)
+        .unwrap_or(Priority::Normal)
+}
+
+/// Plain-text response for a language that isn't loaded: 503 while it's still
+/// loading, otherwise 400 as it isn't supported
+pub fn missing_language(data: &AppState, lang: &str) -> HttpResponse {
+    if data.is_loading(lang) {
+        HttpResponse::ServiceUnavailable()
+            .insert_header(("Retry-After", "5"))
+            .body(format!("Language '{}' is still loading; retry shortly", lang))
+    } else {
+        HttpResponse::BadRequest().body(format!("Language '{}' not supported", lang))
+    }
+}
+
+/// Tag a response with the hash of the lexicon it was computed from so clients can detect
+/// mismatched replicas. Takes the handler's own snapshot rather than looking the language
+/// up again, which after a reload could name a different lexicon than the body.
+pub fn with_lexicon_hash(language: &LanguageData, mut resp: HttpResponse) -> HttpResponse {
+    if let Ok(value) = HeaderValue::from_str(&language.hash) {
+        resp.headers_mut().insert(HeaderName::from_static(LEXICON_HASH_HEADER), value);
+    }
+    resp
+}
+
+/// Mount the public API
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    // JSON bodies are only taken by endpoints that answer with the v2 error envelope
+    cfg.app_data(web::JsonConfig::default().error_handler(|err, _| {
+        v2::error::ApiError::invalid_parameter(err.to_string()).into()
+    }));
+    cfg.service(config::get_langs)
+        .service(config::get_config)
+        .service(validation::check_word_lang)
+        .service(validation::check_word)
+        .service(validation::validate_word_lang)
+        .service(validation::validate_word)
+        .service(random::rand_letter)
+        .service(random::rand_vowel)
+        .service(random::rand_consonant)
+        .service(random::rand_unicorn)
+        .service(random::rand_word)
+        .service(wordle::start_game)
+        .service(wordle::guess)
+        .service(openapi::get_openapi)
+        .service(v2::scope())
+        .service(enveloped_routes());
+}
+
+/// Unversioned routes that answer with the v2 error envelope, including for malformed
+/// query strings and paths. The empty prefix keeps their paths as they are; mounted
+/// last, it only sees requests no other route took.
+fn enveloped_routes() -> Scope {
+    web::scope("")
+        .app_data(v2::error::query_config())
+        .app_data(v2::error::path_config())
+        .service(rack::analyze_rack)
+        .service(rack::rack_anagrams)
+        .service(rack::rack_hint)
+        .service(analysis::analyze_round_plays)
+        .service(ladder::ladder_puzzle)
+        .service(ladder::ladder_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_priority_only_from_internal_peers() {
+        let asking = |peer: Option<&str>| {
+            let mut req = TestRequest::default().insert_header((PRIORITY_HEADER, "high"));
+            if let Some(peer) = peer {
+                req = req.peer_addr(peer.parse().unwrap());
+            }
+            request_priority(&req.to_http_request())
+        };
+        assert_eq!(asking(None), Priority::High);
+        assert_eq!(asking(Some("127.0.0.1:40000")), Priority::High);
+        assert_eq!(asking(Some("[::1]:40000")), Priority::High);
+        assert_eq!(asking(Some("203.0.113.7:40000")), Priority::Normal);
+        assert_eq!(request_priority(&TestRequest::default().to_http_request()), Priority::Normal);
+    }
+
+    #[actix_web::test]
+    async fn test_enveloped_routes_reject_bad_queries_with_the_envelope() {
+        use actix_web::http::StatusCode;
+        use actix_web::{test, App};
+        use crate::services::language::test_language;
+
+        let state = AppState::for_tests(vec![("en", test_language(&["CAT", "ACT", "DOG"]))]);
+        let app = test::init_service(App::new().app_data(web::Data::new(state)).configure(configure)).await;
+        let get = |uri: &'static str| test::TestRequest::get().uri(uri).to_request();
+
+        for uri in [
+            "/rack/analyze/en",
+            "/rack/anagrams/en",
+            "/hint/en?rack=CAT&level=abc",
+            "/ladder/en?from=CAT",
+            "/ladder/en/puzzle?length=four",
+        ] {
+            let resp = test::call_service(&app, get(uri)).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{}", uri);
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(body["error"]["code"], "invalid_parameter", "{}", uri);
+        }
+        assert_eq!(test::call_service(&app, get("/hint/en?rack=CAT")).await.status(), StatusCode::OK);
+
+        // Other unversioned routes keep their plain-text errors, and unknown paths still 404
+        let resp = test::call_service(&app, get("/rand/langs/en/word?count=abc")).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert!(!test::read_body(resp).await.starts_with(b"{"));
+        assert_eq!(test::call_service(&app, get("/nowhere")).await.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,76 @@
+use actix_web::{get, HttpResponse, Responder};
+use utoipa::OpenApi;
+
+use crate::handlers::{admin, analysis, config, explain, ladder, rack, random, stats, v2, validation, wordle};
+
+/// OpenAPI description of every public route, generated from the handler and model types
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "wordd", description = "Word validation and random tile/word service"),
+    paths(
+        config::get_langs,
+        config::get_config,
+        validation::check_word_lang,
+        validation::check_word,
+        validation::validate_word_lang,
+        validation::validate_word,
+        random::rand_letter,
+        random::rand_vowel,
+        random::rand_consonant,
+        random::rand_unicorn,
+        random::rand_word,
+        v2::get_langs,
+        v2::get_config,
+        v2::validate_word,
+        v2::solve,
+        v2::prefix,
+        v2::rand_word,
+        v2::rand_letters,
+        rack::analyze_rack,
+        rack::rack_anagrams,
+        rack::rack_hint,
+        analysis::analyze_round_plays,
+        ladder::ladder_path,
+        ladder::ladder_puzzle,
+        wordle::start_game,
+        wordle::guess,
+    ),
+    tags(
+        (name = "config", description = "Languages, tile bags, and letter classes"),
+        (name = "validation", description = "Plain-text word checks"),
+        (name = "random", description = "Plain-text random letters and words"),
+        (name = "v2", description = "JSON API with a shared error envelope"),
+        (name = "rack", description = "Whole-rack analysis, JSON with the v2 error envelope"),
+        (name = "ladder", description = "Word ladder solo mode, JSON with the v2 error envelope"),
+        (name = "wordle", description = "Daily hidden-word guessing mode, JSON with the v2 error envelope"),
+    )
+)]
+pub struct ApiDoc;
+
+/// OpenAPI description of the admin listener's routes
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "wordd admin", description = "Reloads and lexicon diagnostics"),
+    paths(
+        admin::reload_language,
+        admin::cache_stats,
+        stats::get_rejections,
+        stats::get_memory,
+        explain::explain_word,
+    ),
+    tags(
+        (name = "admin", description = "Operational controls"),
+        (name = "diagnostics", description = "Lexicon curation helpers"),
+    )
+)]
+pub struct AdminApiDoc;
+
+#[get("/openapi.json")]
+pub async fn get_openapi() -> impl Responder {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+#[get("/openapi.json")]
+pub async fn get_admin_openapi() -> impl Responder {
+    HttpResponse::Ok().json(AdminApiDoc::openapi())
+}
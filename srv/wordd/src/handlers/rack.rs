@@ -0,0 +1,125 @@
+//! JSON endpoints that study a whole rack rather than pick or check single words.
+//! Failures use the v2 error envelope.
+
+use std::sync::Arc;
+
+use actix_web::{get, web, HttpRequest, HttpResponse};
+
+use crate::handlers::random::{cached_matches, require_language};
+use crate::handlers::v2::error::ApiError;
+use crate::handlers::{request_priority, with_lexicon_hash};
+use crate::models::{AppState, HintQuery, RackAnalyzeQuery, RackQuery};
+use crate::services::cache::RackKey;
+use crate::services::generator::WordFilters;
+use crate::services::hints::hint;
+use crate::services::rack_analysis::{analyze, full_rack_words, Balance};
+use crate::services::scoring::{default_values, parse_values};
+
+#[utoipa::path(tag = "rack",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RackAnalyzeQuery, WordFilters),
+    responses(
+        (status = 200, description = "Playability, best score, balance, and difficulty of the rack", body = crate::services::rack_analysis::RackAnalysis),
+        (status = 400, description = "Invalid letter values or a rack past the length cap", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+        (status = 503, description = "Compute queue full, query timed out, or language still loading", body = ApiError),
+    ))]
+#[get("/rack/analyze/{lang}")]
+pub async fn analyze_rack(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<RackAnalyzeQuery>,
+    filters: web::Query<WordFilters>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let lang = path.into_inner().to_lowercase();
+    let query = query.into_inner();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let values = match query.values.as_deref() {
+        Some(spec) => parse_values(spec).map_err(ApiError::invalid_parameter)?,
+        None => default_values(&language.tile_bag, &language.unicorns),
+    };
+    let balance = Balance {
+        min_vowels: query.min_vowels.unwrap_or(1),
+        min_consonants: query.min_consonants.unwrap_or(1),
+    };
+
+    let priority = request_priority(&req);
+    let key = RackKey::new(&lang, &language.hash, &query.rack, None, None, &filters.into_inner().normalized());
+    let matches = cached_matches(&data, &language, key, priority)
+        .await
+        .map_err(|e| ApiError::from_query(e, &lang))?;
+
+    let (analysis_lang, analyzed) = (lang.clone(), Arc::clone(&language));
+    let analysis = data.compute.run(priority, move || {
+        analyze(&analysis_lang, &query.rack, &analyzed.words, &matches, &values, &analyzed.vowels, &balance)
+    }).await.map_err(|e| ApiError::from_query(e, &lang))?;
+
+    Ok(with_lexicon_hash(&language, HttpResponse::Ok().json(analysis)))
+}
+
+#[utoipa::path(tag = "rack",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RackQuery),
+    responses(
+        (status = 200, description = "Words using every tile, and near misses using all but one", body = crate::services::rack_analysis::FullRackWords),
+        (status = 400, description = "Rack past the length cap", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+        (status = 503, description = "Compute queue full, query timed out, or language still loading", body = ApiError),
+    ))]
+#[get("/rack/anagrams/{lang}")]
+pub async fn rack_anagrams(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<RackQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let lang = path.into_inner().to_lowercase();
+    let rack = query.into_inner().rack;
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+
+    let key = RackKey::new(&lang, &language.hash, &rack, None, None, &WordFilters::default());
+    let matches = cached_matches(&data, &language, key, request_priority(&req))
+        .await
+        .map_err(|e| ApiError::from_query(e, &lang))?;
+
+    // Shorter words are skipped on length alone, so this is cheap enough inline
+    let found = full_rack_words(&lang, &rack, &language.words, &matches);
+    Ok(with_lexicon_hash(&language, HttpResponse::Ok().json(found)))
+}
+
+#[utoipa::path(tag = "rack",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), HintQuery),
+    responses(
+        (status = 200, description = "Hints about the rack's best word, more revealing at higher levels", body = crate::services::hints::Hint),
+        (status = 400, description = "Invalid letter values or a rack past the length cap", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+        (status = 503, description = "Compute queue full, query timed out, or language still loading", body = ApiError),
+    ))]
+#[get("/hint/{lang}")]
+pub async fn rack_hint(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<HintQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let lang = path.into_inner().to_lowercase();
+    let query = query.into_inner();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let values = match query.values.as_deref() {
+        Some(spec) => parse_values(spec).map_err(ApiError::invalid_parameter)?,
+        None => default_values(&language.tile_bag, &language.unicorns),
+    };
+
+    let priority = request_priority(&req);
+    let key = RackKey::new(&lang, &language.hash, &query.rack, None, None, &WordFilters::default());
+    let matches = cached_matches(&data, &language, key, priority)
+        .await
+        .map_err(|e| ApiError::from_query(e, &lang))?;
+
+    let (hint_lang, hinted) = (lang.clone(), Arc::clone(&language));
+    let level = query.level.unwrap_or(1);
+    let found = data.compute.run(priority, move || {
+        hint(&hint_lang, &query.rack, &hinted.words, &matches, &values, level)
+    }).await.map_err(|e| ApiError::from_query(e, &lang))?;
+
+    Ok(with_lexicon_hash(&language, HttpResponse::Ok().json(found)))
+}
@@ -1,7 +1,32 @@
-use actix_web::{get, web, HttpResponse, Responder};
-use crate::models::{AppState, RandQuery};
+use std::sync::Arc;
+
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+use crate::models::{AppState, LanguageData, RandQuery, SolveQuery};
+use crate::handlers::{missing_language, request_priority, with_lexicon_hash};
+use crate::services::budget::{decode_cursor, encode_cursor, QueryError};
+use crate::services::cache::RackKey;
+use crate::services::likelihood;
+use crate::services::generator::{pick_random, rack_matches, select_random_words_with_constraints, WordConstraints, WordFilters};
+use crate::services::solver;
+use crate::services::compute::Priority;
 use crate::utils::{select_random_from_bag, select_random_from_list};
 
+/// Set on plain-text responses whose results were cut short by the query budget
+pub const TRUNCATED_HEADER: &str = "x-truncated";
+
+/// Plain-text 503 for when the compute queue is full
+pub fn overloaded() -> HttpResponse {
+    HttpResponse::ServiceUnavailable()
+        .insert_header(("Retry-After", "1"))
+        .body("Too many rack queries queued; retry shortly")
+}
+
+#[utoipa::path(tag = "random",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RandQuery),
+    responses(
+        (status = 200, description = "Newline-separated random letters", body = String, content_type = "text/plain"),
+        (status = 400, description = "Language not supported", body = String, content_type = "text/plain"),
+    ))]
 #[get("/rand/langs/{lang}/letter")]
 pub async fn rand_letter(
     data: web::Data<AppState>,
@@ -9,18 +34,24 @@ pub async fn rand_letter(
     query: web::Query<RandQuery>,
 ) -> impl Responder {
     let lang = path.into_inner().to_lowercase();
-    let count = query.count.unwrap_or(1);
+    let count = query.count.unwrap_or(1).min(data.query_limits.max_results);
     
-    let bag = match data.tile_bags.get(&lang) {
-        Some(b) => b,
-        None => return HttpResponse::BadRequest().body(format!("Language '{}' not supported", lang)),
+    let language = match data.language(&lang) {
+        Some(l) => l,
+        None => return missing_language(&data, &lang),
     };
     
-    let letters = select_random_from_bag(bag, count);
+    let letters = select_random_from_bag(&language.tile_bag, count);
     let output = letters.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("\n");
-    HttpResponse::Ok().content_type("text/plain").body(output)
+    with_lexicon_hash(&language, HttpResponse::Ok().content_type("text/plain").body(output))
 }
 
+#[utoipa::path(tag = "random",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RandQuery),
+    responses(
+        (status = 200, description = "Newline-separated random vowels", body = String, content_type = "text/plain"),
+        (status = 400, description = "Language not supported", body = String, content_type = "text/plain"),
+    ))]
 #[get("/rand/langs/{lang}/vowel")]
 pub async fn rand_vowel(
     data: web::Data<AppState>,
@@ -28,18 +59,24 @@ pub async fn rand_vowel(
     query: web::Query<RandQuery>,
 ) -> impl Responder {
     let lang = path.into_inner().to_lowercase();
-    let count = query.count.unwrap_or(1);
+    let count = query.count.unwrap_or(1).min(data.query_limits.max_results);
     
-    let vowels = match data.vowel_sets.get(&lang) {
-        Some(v) => v,
-        None => return HttpResponse::BadRequest().body(format!("Language '{}' not supported", lang)),
+    let language = match data.language(&lang) {
+        Some(l) => l,
+        None => return missing_language(&data, &lang),
     };
     
-    let selected = select_random_from_list(vowels, count);
+    let selected = select_random_from_list(&language.vowels, count);
     let output = selected.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("\n");
-    HttpResponse::Ok().content_type("text/plain").body(output)
+    with_lexicon_hash(&language, HttpResponse::Ok().content_type("text/plain").body(output))
 }
 
+#[utoipa::path(tag = "random",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RandQuery),
+    responses(
+        (status = 200, description = "Newline-separated random consonants", body = String, content_type = "text/plain"),
+        (status = 400, description = "Language not supported", body = String, content_type = "text/plain"),
+    ))]
 #[get("/rand/langs/{lang}/consonant")]
 pub async fn rand_consonant(
     data: web::Data<AppState>,
@@ -47,18 +84,24 @@ pub async fn rand_consonant(
     query: web::Query<RandQuery>,
 ) -> impl Responder {
     let lang = path.into_inner().to_lowercase();
-    let count = query.count.unwrap_or(1);
+    let count = query.count.unwrap_or(1).min(data.query_limits.max_results);
     
-    let consonants = match data.consonant_sets.get(&lang) {
-        Some(c) => c,
-        None => return HttpResponse::BadRequest().body(format!("Language '{}' not supported", lang)),
+    let language = match data.language(&lang) {
+        Some(l) => l,
+        None => return missing_language(&data, &lang),
     };
     
-    let selected = select_random_from_list(consonants, count);
+    let selected = select_random_from_list(&language.consonants, count);
     let output = selected.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("\n");
-    HttpResponse::Ok().content_type("text/plain").body(output)
+    with_lexicon_hash(&language, HttpResponse::Ok().content_type("text/plain").body(output))
 }
 
+#[utoipa::path(tag = "random",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RandQuery),
+    responses(
+        (status = 200, description = "Newline-separated random unicorns", body = String, content_type = "text/plain"),
+        (status = 400, description = "Language not supported", body = String, content_type = "text/plain"),
+    ))]
 #[get("/rand/langs/{lang}/unicorn")]
 pub async fn rand_unicorn(
     data: web::Data<AppState>,
@@ -66,46 +109,212 @@ pub async fn rand_unicorn(
     query: web::Query<RandQuery>,
 ) -> impl Responder {
     let lang = path.into_inner().to_lowercase();
-    let count = query.count.unwrap_or(1);
+    let count = query.count.unwrap_or(1).min(data.query_limits.max_results);
     
-    let unicorns = match data.unicorn_sets.get(&lang) {
-        Some(u) => u,
-        None => return HttpResponse::BadRequest().body(format!("Language '{}' not supported", lang)),
+    let language = match data.language(&lang) {
+        Some(l) => l,
+        None => return missing_language(&data, &lang),
     };
     
-    let selected = select_random_from_list(unicorns, count);
+    let selected = select_random_from_list(&language.unicorns, count);
     let output = selected.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("\n");
-    HttpResponse::Ok().content_type("text/plain").body(output)
+    with_lexicon_hash(&language, HttpResponse::Ok().content_type("text/plain").body(output))
+}
+
+/// A language's data, or why a query against it can't run
+pub fn require_language(data: &AppState, lang: &str) -> Result<Arc<LanguageData>, QueryError> {
+    data.language(lang).ok_or_else(|| {
+        if data.is_loading(lang) { QueryError::Loading } else { QueryError::UnsupportedLanguage }
+    })
+}
+
+/// Pick `query.count` random words from `language`, honouring any rack constraints and
+/// filters. Rack picks draw from the cached answer for the canonical rack, computing it
+/// on the compute pool on a miss. Other constrained picks scan the lexicon on the pool,
+/// bounded by the request's time budget; the flag reports whether that cut the scan short.
+pub async fn random_words(
+    data: &AppState,
+    lang: &str,
+    language: &Arc<LanguageData>,
+    query: &RandQuery,
+    filters: &WordFilters,
+    priority: Priority,
+) -> Result<(Vec<String>, bool), QueryError> {
+    let count = query.count.unwrap_or(1).min(data.query_limits.max_results);
+    let filters = filters.clone().normalized();
+
+    if let Some(letters) = query.letters.as_deref() {
+        let key = RackKey::new(lang, &language.hash, letters, query.min_vowels, query.min_consonants, &filters);
+        let matches = cached_matches(data, language, key, priority).await?;
+        return Ok((pick_random(&language.words, &matches, count), false));
+    }
+
+    let language = Arc::clone(language);
+
+    let budget = data.query_limits.budget(None, query.timeout_ms);
+    let min_vowels = query.min_vowels;
+    let min_consonants = query.min_consonants;
+    let unfiltered = min_vowels.is_none() && min_consonants.is_none() && filters.is_empty();
+    let pick = move || {
+        // Get language-specific vowels for constraint validation
+        let constraints = WordConstraints {
+            letters: None,
+            min_vowels,
+            min_consonants,
+            vowels: &language.vowels,
+            filters: &filters,
+            unicorns: &language.unicorns,
+        };
+        select_random_words_with_constraints(&language.words, &language.index, count, constraints, &budget)
+    };
+
+    if !unfiltered {
+        data.compute.run(priority, pick).await
+    } else {
+        Ok(pick())
+    }
+}
+
+/// The complete answer to a rack query in solver order, from the rack cache or else
+/// computed on the compute pool and cached. Racks past the server's length cap are
+/// refused, and a computation that outruns the server's time budget fails rather than
+/// caching a partial answer.
+pub async fn cached_matches(
+    data: &AppState,
+    language: &Arc<LanguageData>,
+    key: RackKey,
+    priority: Priority,
+) -> Result<Arc<Vec<u32>>, QueryError> {
+    data.query_limits.check_rack(key.rack())?;
+    if let Some(matches) = data.rack_cache.get(&key) {
+        return Ok(matches);
+    }
+
+    // Starts now, so time spent queued for the pool counts against it
+    let budget = data.query_limits.budget(None, None);
+    let language = Arc::clone(language);
+    let rack = key.rack().to_string();
+    let (min_vowels, min_consonants) = (key.min_vowels(), key.min_consonants());
+    let filters = key.filters().clone();
+    let matches = data.compute.run(priority, move || {
+        let constraints = WordConstraints {
+            letters: Some(&rack),
+            min_vowels,
+            min_consonants,
+            vowels: &language.vowels,
+            filters: &filters,
+            unicorns: &language.unicorns,
+        };
+        let mut matches = rack_matches(&language.words, &language.index, &rack, &constraints, &budget)?;
+        solver::sort_matches(&language.words, &mut matches);
+        Some(Arc::new(matches))
+    }).await?.ok_or(QueryError::TimedOut)?;
+
+    data.rack_cache.insert(key, Arc::clone(&matches));
+    Ok(matches)
 }
 
+/// Duplicate-likelihood estimates for solver results on `letters`, parallel to `words`
+pub fn duplicate_likelihoods(language: &LanguageData, letters: &str, words: &[String]) -> Vec<f32> {
+    likelihood::duplicate_likelihoods(&language.commonness, &language.words, letters, words)
+}
+
+/// One page of solver results, with the cursor to fetch the next if the budget ran out first
+pub struct SolvePage {
+    pub words: Vec<String>,
+    pub next_cursor: Option<String>,
+    /// The data the page was solved against, so follow-up work sees the same lexicon
+    pub language: Arc<LanguageData>,
+}
+
+/// Words formable from `query.letters` in a language that pass `filters`, one page at a
+/// time. The full answer comes from the rack cache or the compute pool; `query.limit` is
+/// clamped to the server cap.
+pub async fn solve_rack(
+    data: &AppState,
+    lang: &str,
+    query: &SolveQuery,
+    filters: &WordFilters,
+    priority: Priority,
+) -> Result<SolvePage, QueryError> {
+    let language = require_language(data, lang)?;
+    let start = match query.cursor.as_deref() {
+        Some(c) => decode_cursor(&language.hash, c)?,
+        None => 0,
+    };
+
+    // Computing the full answer has its own deadline in cached_matches, so only the
+    // result limit applies to the page
+    let budget = data.query_limits.budget(query.limit, None);
+    let key = RackKey::new(lang, &language.hash, &query.letters, None, None, &filters.clone().normalized());
+    let matches = cached_matches(data, &language, key, priority).await?;
+    let page = solver::page(&language.words, &matches, start, &budget);
+    Ok(SolvePage {
+        next_cursor: page.next_position.map(|pos| encode_cursor(&language.hash, pos)),
+        words: page.words,
+        language,
+    })
+}
+
+#[utoipa::path(tag = "random",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RandQuery, WordFilters),
+    responses(
+        (status = 200, description = "Newline-separated random words; `x-truncated: true` if the time budget cut the candidate scan short", body = String, content_type = "text/plain"),
+        (status = 400, description = "Language not supported, or a rack past the length cap", body = String, content_type = "text/plain"),
+        (status = 503, description = "Compute queue full, or the rack query timed out", body = String, content_type = "text/plain"),
+    ))]
 #[get("/rand/langs/{lang}/word")]
 pub async fn rand_word(
     data: web::Data<AppState>,
     path: web::Path<String>,
     query: web::Query<RandQuery>,
+    filters: web::Query<WordFilters>,
+    req: HttpRequest,
 ) -> impl Responder {
     let lang = path.into_inner().to_lowercase();
-    let count = query.count.unwrap_or(1);
-    
-    let words = match data.word_lists.get(&lang) {
-        Some(w) => w,
-        None => return HttpResponse::BadRequest().body(format!("Language '{}' not supported", lang)),
+    let language = match data.language(&lang) {
+        Some(l) => l,
+        None => return missing_language(&data, &lang),
     };
-    
-    // Get language-specific vowels for constraint validation
-    let vowels = data.vowel_sets.get(&lang)
-        .map(|v| v.as_slice())
-        .unwrap_or(&['A', 'E', 'I', 'O', 'U']);
-    
-    let constraints = crate::services::generator::WordConstraints {
-        letters: query.letters.as_deref(),
-        min_vowels: query.min_vowels,
-        min_consonants: query.min_consonants,
-        vowels,
+
+    let (selected, truncated) = match random_words(&data, &lang, &language, &query, &filters, request_priority(&req)).await {
+        Ok(s) => s,
+        Err(QueryError::Overloaded) => return overloaded(),
+        Err(QueryError::UnsupportedLanguage | QueryError::Loading) => return missing_language(&data, &lang),
+        Err(QueryError::InvalidRack(message)) => return HttpResponse::BadRequest().body(message),
+        Err(QueryError::TimedOut) => return HttpResponse::ServiceUnavailable().body("Query ran past the server's time budget"),
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Query failed: {:?}", e)),
     };
-    
-    let selected = crate::services::generator::select_random_words_with_constraints(words, count, constraints);
 
     let output = selected.join("\n");
-    HttpResponse::Ok().content_type("text/plain").body(output)
+    let mut resp = HttpResponse::Ok();
+    resp.content_type("text/plain");
+    if truncated {
+        resp.insert_header((TRUNCATED_HEADER, "true"));
+    }
+    with_lexicon_hash(&language, resp.body(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::{test, web, App};
+
+    use crate::handlers;
+    use crate::models::AppState;
+    use crate::services::language::test_language;
+
+    #[actix_web::test]
+    async fn test_letter_counts_are_capped() {
+        let state = AppState::for_tests(vec![("en", test_language(&["CAT", "ACT", "DOG"]))]);
+        let app = test::init_service(App::new().app_data(web::Data::new(state)).configure(handlers::configure)).await;
+        for kind in ["letter", "vowel", "consonant"] {
+            let req = test::TestRequest::get().uri(&format!("/rand/langs/en/{}?count=100000000000", kind)).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let body = test::read_body(resp).await;
+            // AppState::for_tests caps results at 1000
+            assert_eq!(body.split(|&b| b == b'\n').count(), 1000, "kind {}", kind);
+        }
+    }
 }
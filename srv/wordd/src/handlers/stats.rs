@@ -0,0 +1,64 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use chrono::{DateTime, NaiveDate};
+use crate::handlers::missing_language;
+use crate::models::{AppState, LanguageMemory, RejectionQuery};
+
+/// Parse `since` as unix seconds, an RFC 3339 timestamp, or a plain YYYY-MM-DD date
+fn parse_since(since: &str) -> Option<i64> {
+    if let Ok(secs) = since.parse::<i64>() {
+        return Some(secs);
+    }
+    if let Ok(ts) = DateTime::parse_from_rfc3339(since) {
+        return Some(ts.timestamp());
+    }
+    NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+#[utoipa::path(tag = "diagnostics",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RejectionQuery),
+    responses(
+        (status = 200, description = "Most-attempted rejected words", body = crate::services::rejections::RejectionReport),
+        (status = 400, description = "Language not supported or bad 'since'", body = String, content_type = "text/plain"),
+    ))]
+#[get("/stats/{lang}/rejections")]
+pub async fn get_rejections(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<RejectionQuery>,
+) -> impl Responder {
+    let lang = path.into_inner().to_lowercase();
+    let language = match data.language(&lang) {
+        Some(l) => l,
+        None => return missing_language(&data, &lang),
+    };
+
+    let since = match query.since.as_deref() {
+        Some(s) => match parse_since(s) {
+            Some(ts) => ts,
+            None => return HttpResponse::BadRequest().body(format!("Invalid 'since' value: {}", s)),
+        },
+        None => 0,
+    };
+    let limit = query.limit.unwrap_or(50);
+
+    HttpResponse::Ok().json(data.rejections.report(&lang, since, limit, &language.inputs.deletions))
+}
+
+#[utoipa::path(tag = "diagnostics",
+    responses((status = 200, description = "Memory held by each loaded language's lexicon and index", body = Vec<LanguageMemory>)))]
+#[get("/stats/memory")]
+pub async fn get_memory(data: web::Data<AppState>) -> impl Responder {
+    let usage: Vec<LanguageMemory> = data.supported_langs.iter()
+        .filter_map(|lang| data.loaded(lang).map(|l| LanguageMemory {
+            language: lang.clone(),
+            word_count: l.words.len(),
+            lexicon: l.words.memory(),
+            index_bytes: l.index.memory(),
+            ladder_bytes: l.ladders.memory(),
+        }))
+        .collect();
+    HttpResponse::Ok().json(usage)
+}
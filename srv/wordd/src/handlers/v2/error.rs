@@ -0,0 +1,118 @@
+use std::fmt;
+
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse, ResponseError};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::services::budget::QueryError;
+
+/// Machine-readable error codes shared by every v2 endpoint
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    UnsupportedLanguage,
+    InvalidParameter,
+    NotFound,
+    Overloaded,
+    /// The query ran past the server's time budget
+    TimedOut,
+    LanguageLoading,
+    Internal,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ErrorBody {
+    pub code: ErrorCode,
+    pub message: String,
+    pub language: Option<String>,
+}
+
+/// The single error envelope: `{"error": {"code", "message", "language"}}`
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ApiError {
+    pub error: ErrorBody,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>, language: Option<&str>) -> Self {
+        ApiError {
+            error: ErrorBody {
+                code,
+                message: message.into(),
+                language: language.map(str::to_string),
+            },
+        }
+    }
+
+    pub fn unsupported_language(lang: &str) -> Self {
+        Self::new(ErrorCode::UnsupportedLanguage, format!("Language '{}' not supported", lang), Some(lang))
+    }
+
+    pub fn language_loading(lang: &str) -> Self {
+        Self::new(ErrorCode::LanguageLoading, format!("Language '{}' is still loading; retry shortly", lang), Some(lang))
+    }
+
+    pub fn invalid_parameter(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidParameter, message, None)
+    }
+
+    /// Map a failed rack query onto the envelope
+    pub fn from_query(err: QueryError, lang: &str) -> Self {
+        match err {
+            QueryError::UnsupportedLanguage => Self::unsupported_language(lang),
+            QueryError::Loading => Self::language_loading(lang),
+            QueryError::Overloaded => Self::new(
+                ErrorCode::Overloaded,
+                "Too many rack queries queued; retry shortly",
+                Some(lang),
+            ),
+            QueryError::InvalidCursor(message) | QueryError::InvalidRack(message) => {
+                Self::new(ErrorCode::InvalidParameter, message, Some(lang))
+            }
+            QueryError::TimedOut => Self::new(
+                ErrorCode::TimedOut,
+                "Query ran past the server's time budget; try a shorter rack or fewer blanks",
+                Some(lang),
+            ),
+            QueryError::Failed => Self::new(ErrorCode::Internal, "Query failed", Some(lang)),
+        }
+    }
+}
+
+/// Query-string extraction with failures mapped onto the envelope
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|err, _| ApiError::invalid_parameter(err.to_string()).into())
+}
+
+/// Path extraction with failures mapped onto the envelope
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(|err, _| ApiError::invalid_parameter(err.to_string()).into())
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.error.code, self.error.message)
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self.error.code {
+            ErrorCode::UnsupportedLanguage | ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::InvalidParameter => StatusCode::BAD_REQUEST,
+            ErrorCode::Overloaded | ErrorCode::TimedOut | ErrorCode::LanguageLoading => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut resp = HttpResponse::build(self.status_code());
+        match self.error.code {
+            ErrorCode::Overloaded => { resp.insert_header(("Retry-After", "1")); }
+            ErrorCode::LanguageLoading => { resp.insert_header(("Retry-After", "5")); }
+            _ => {}
+        }
+        resp.json(self)
+    }
+}
@@ -0,0 +1,292 @@
+//! Versioned JSON API. Every endpoint returns typed JSON and every failure uses the
+//! `ApiError` envelope; the unversioned routes stay as they are for existing clients.
+
+use actix_web::{get, web, HttpRequest, HttpResponse, Scope};
+use actix_web::http::header::ETag;
+use crate::handlers::config::{build_config, config_etag, is_not_modified, lang_infos};
+use crate::handlers::random::{duplicate_likelihoods, random_words, require_language, solve_rack};
+use crate::handlers::validation::lookup_word;
+use crate::handlers::{request_priority, with_lexicon_hash};
+use crate::models::{AppState, LangsResponse, LettersResponse, PrefixQuery, PrefixResponse, RandQuery, SolveQuery, SolveResponse, ValidationResponse, WordsResponse};
+use crate::services::generator::WordFilters;
+use crate::utils::{select_random_from_bag, select_random_from_list};
+
+pub mod error;
+
+use error::{ApiError, ErrorCode};
+
+type ApiResult = Result<HttpResponse, ApiError>;
+
+/// Mount the v2 API, with extractor failures and unknown routes mapped onto the error envelope
+pub fn scope() -> Scope {
+    web::scope("/v2")
+        .app_data(error::query_config())
+        .app_data(error::path_config())
+        .service(get_langs)
+        .service(get_config)
+        .service(validate_word)
+        .service(solve)
+        .service(prefix)
+        .service(rand_word)
+        .service(rand_letters)
+        .default_service(web::to(|req: HttpRequest| async move {
+            Err::<HttpResponse, _>(ApiError::new(
+                ErrorCode::NotFound,
+                format!("No such endpoint: {}", req.path()),
+                None,
+            ))
+        }))
+}
+
+#[utoipa::path(context_path = "/v2", tag = "v2", operation_id = "v2_get_langs",
+    responses((status = 200, description = "Supported languages", body = LangsResponse)))]
+#[get("/langs")]
+pub async fn get_langs(data: web::Data<AppState>) -> ApiResult {
+    Ok(HttpResponse::Ok().json(LangsResponse { languages: lang_infos(&data) }))
+}
+
+#[utoipa::path(context_path = "/v2", tag = "v2", operation_id = "v2_get_config",
+    params(("lang" = String, Path, description = "Language code, e.g. en")),
+    responses(
+        (status = 200, description = "Tile bag and letter classes; the ETag is the lexicon hash", body = crate::models::ConfigResponse),
+        (status = 304, description = "If-None-Match matched the current lexicon hash"),
+        (status = 404, description = "Language not supported", body = ApiError),
+    ))]
+#[get("/config/{lang}")]
+pub async fn get_config(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+) -> ApiResult {
+    let lang = path.into_inner().to_lowercase();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+
+    let etag = config_etag(&language);
+    if is_not_modified(&req, &etag) {
+        return Ok(HttpResponse::NotModified().insert_header(ETag(etag)).finish());
+    }
+    Ok(HttpResponse::Ok().insert_header(ETag(etag)).json(build_config(&lang, &language)))
+}
+
+#[utoipa::path(context_path = "/v2", tag = "v2", operation_id = "v2_validate_word",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), ("word" = String, Path, description = "Word to look up (case-insensitive)")),
+    responses(
+        (status = 200, description = "Whether the word is in the lexicon", body = ValidationResponse),
+        (status = 404, description = "Language not supported", body = ApiError),
+    ))]
+#[get("/validate/{lang}/{word}")]
+pub async fn validate_word(
+    data: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> ApiResult {
+    let (lang, word) = path.into_inner();
+    let lang = lang.to_lowercase();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let valid = lookup_word(&data, &language, &lang, &word);
+    let word_upper = word.to_uppercase();
+
+    let resp = HttpResponse::Ok().json(ValidationResponse { language: lang.clone(), word: word_upper, valid });
+    Ok(with_lexicon_hash(&language, resp))
+}
+
+#[utoipa::path(context_path = "/v2", tag = "v2", operation_id = "v2_solve",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), SolveQuery, WordFilters),
+    responses(
+        (status = 200, description = "A page of words that can be formed from the rack", body = SolveResponse),
+        (status = 400, description = "Invalid query parameter or cursor, or a rack past the length cap", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+        (status = 503, description = "Compute queue full or query timed out", body = ApiError),
+    ))]
+#[get("/solve/{lang}")]
+pub async fn solve(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<SolveQuery>,
+    filters: web::Query<WordFilters>,
+    req: HttpRequest,
+) -> ApiResult {
+    let lang = path.into_inner().to_lowercase();
+    let query = query.into_inner();
+    let priority = request_priority(&req);
+    let page = solve_rack(&data, &lang, &query, &filters, priority)
+        .await
+        .map_err(|e| ApiError::from_query(e, &lang))?;
+
+    let duplicate_likelihood = query
+        .likelihood
+        .unwrap_or(false)
+        .then(|| duplicate_likelihoods(&page.language, &query.letters, &page.words));
+    let resp = HttpResponse::Ok().json(SolveResponse {
+        language: lang.clone(),
+        letters: query.letters,
+        words: page.words,
+        truncated: page.next_cursor.is_some(),
+        next_cursor: page.next_cursor,
+        duplicate_likelihood,
+    });
+    Ok(with_lexicon_hash(&page.language, resp))
+}
+
+#[utoipa::path(context_path = "/v2", tag = "v2", operation_id = "v2_prefix",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), ("prefix" = String, Path, description = "Word start (case-insensitive)"), PrefixQuery),
+    responses(
+        (status = 200, description = "Words starting with the prefix, alphabetically", body = PrefixResponse),
+        (status = 404, description = "Language not supported", body = ApiError),
+    ))]
+#[get("/prefix/{lang}/{prefix}")]
+pub async fn prefix(
+    data: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    query: web::Query<PrefixQuery>,
+) -> ApiResult {
+    let (lang, prefix) = path.into_inner();
+    let lang = lang.to_lowercase();
+    let prefix = prefix.to_uppercase();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let limit = query.limit.unwrap_or(100).min(data.query_limits.max_results);
+
+    let (words, truncated) = language.words.with_prefix(&prefix, limit);
+    let resp = HttpResponse::Ok().json(PrefixResponse { language: lang.clone(), prefix, words, truncated });
+    Ok(with_lexicon_hash(&language, resp))
+}
+
+#[utoipa::path(context_path = "/v2", tag = "v2", operation_id = "v2_rand_word",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), RandQuery, WordFilters),
+    responses(
+        (status = 200, description = "Random words matching the constraints", body = WordsResponse),
+        (status = 400, description = "Invalid query parameter or a rack past the length cap", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+        (status = 503, description = "Compute queue full or query timed out", body = ApiError),
+    ))]
+#[get("/rand/{lang}/word")]
+pub async fn rand_word(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<RandQuery>,
+    filters: web::Query<WordFilters>,
+    req: HttpRequest,
+) -> ApiResult {
+    let lang = path.into_inner().to_lowercase();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let (words, truncated) = random_words(&data, &lang, &language, &query, &filters, request_priority(&req))
+        .await
+        .map_err(|e| ApiError::from_query(e, &lang))?;
+
+    let resp = HttpResponse::Ok().json(WordsResponse { language: lang.clone(), words, truncated });
+    Ok(with_lexicon_hash(&language, resp))
+}
+
+#[utoipa::path(context_path = "/v2", tag = "v2", operation_id = "v2_rand_letters",
+    params(("lang" = String, Path, description = "Language code, e.g. en"),
+        ("kind" = String, Path, description = "letter, vowel, consonant, or unicorn"),
+        RandQuery),
+    responses(
+        (status = 200, description = "Random letters of the requested kind, at most the server's --max-results", body = LettersResponse),
+        (status = 400, description = "Unknown kind or invalid query parameter", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+    ))]
+#[get("/rand/{lang}/{kind}")]
+pub async fn rand_letters(
+    data: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    query: web::Query<RandQuery>,
+) -> ApiResult {
+    let (lang, kind) = path.into_inner();
+    let lang = lang.to_lowercase();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let count = query.count.unwrap_or(1).min(data.query_limits.max_results);
+
+    let letters = match kind.as_str() {
+        "letter" => select_random_from_bag(&language.tile_bag, count),
+        "vowel" => select_random_from_list(&language.vowels, count),
+        "consonant" => select_random_from_list(&language.consonants, count),
+        "unicorn" => select_random_from_list(&language.unicorns, count),
+        _ => return Err(ApiError::new(
+            ErrorCode::InvalidParameter,
+            format!("Unknown letter kind '{}' (expected letter, vowel, consonant, or unicorn)", kind),
+            Some(&lang),
+        )),
+    };
+
+    let resp = HttpResponse::Ok().json(LettersResponse { language: lang.clone(), letters });
+    Ok(with_lexicon_hash(&language, resp))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::dev::ServiceResponse;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+
+    use crate::handlers;
+    use crate::models::AppState;
+    use crate::services::language::test_language;
+
+    async fn call(req: test::TestRequest) -> ServiceResponse {
+        let mut state = AppState::for_tests(vec![("en", test_language(&["CAT", "ACT", "DOG"]))]);
+        state.supported_langs.push("fr".to_string());
+        let app = test::init_service(App::new().app_data(web::Data::new(state)).configure(handlers::configure)).await;
+        test::call_service(&app, req.to_request()).await
+    }
+
+    /// Status and the envelope's code and language; the message is free text
+    async fn error_of(req: test::TestRequest) -> (StatusCode, Value, Value) {
+        let resp = call(req).await;
+        let status = resp.status();
+        let body: Value = test::read_body_json(resp).await;
+        assert!(body["error"]["message"].is_string(), "{}", body);
+        (status, body["error"]["code"].clone(), body["error"]["language"].clone())
+    }
+
+    #[actix_web::test]
+    async fn test_error_envelope() {
+        assert_eq!(
+            error_of(test::TestRequest::get().uri("/v2/solve/xx?letters=CAT")).await,
+            (StatusCode::NOT_FOUND, json!("unsupported_language"), json!("xx")),
+        );
+        assert_eq!(
+            error_of(test::TestRequest::get().uri("/v2/config/fr")).await,
+            (StatusCode::SERVICE_UNAVAILABLE, json!("language_loading"), json!("fr")),
+        );
+        assert_eq!(
+            error_of(test::TestRequest::get().uri("/v2/solve/en?letters=CAT&limit=many")).await,
+            (StatusCode::BAD_REQUEST, json!("invalid_parameter"), Value::Null),
+        );
+        assert_eq!(
+            error_of(test::TestRequest::get().uri("/v2/solve/en?letters=CAT&cursor=bogus")).await,
+            (StatusCode::BAD_REQUEST, json!("invalid_parameter"), json!("en")),
+        );
+        assert_eq!(
+            error_of(test::TestRequest::get().uri("/v2/nowhere")).await,
+            (StatusCode::NOT_FOUND, json!("not_found"), Value::Null),
+        );
+        // Malformed JSON bodies go through the app-wide JSON error handler
+        assert_eq!(
+            error_of(test::TestRequest::post().uri("/wordle/en/guess")
+                .insert_header(("content-type", "application/json"))
+                .set_payload("{\"guess\": ")).await,
+            (StatusCode::BAD_REQUEST, json!("invalid_parameter"), Value::Null),
+        );
+
+        // AppState::for_tests allows racks of up to 16 tiles
+        assert_eq!(
+            error_of(test::TestRequest::get().uri("/v2/solve/en?letters=CATCATCATCATCAT__")).await,
+            (StatusCode::BAD_REQUEST, json!("invalid_parameter"), json!("en")),
+        );
+
+        let resp = call(test::TestRequest::get().uri("/v2/config/fr")).await;
+        assert_eq!(resp.headers().get("retry-after").unwrap(), "5");
+        let resp = call(test::TestRequest::get().uri("/v2/solve/en?letters=TAC")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_letter_counts_are_capped() {
+        let resp = call(test::TestRequest::get().uri("/v2/rand/en/letter?count=100000000000")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        // AppState::for_tests caps results at 1000
+        assert_eq!(body["letters"].as_array().unwrap().len(), 1000);
+    }
+}
@@ -1,27 +1,36 @@
 use actix_web::{get, web, HttpResponse, Responder};
-use crate::models::AppState;
+use crate::models::{AppState, LanguageData};
+use crate::handlers::{missing_language, with_lexicon_hash};
 use log::info;
 
+/// Look a word up in `language`, the lexicon for `lang`, recording it if rejected
+pub fn lookup_word(data: &AppState, language: &LanguageData, lang: &str, word: &str) -> bool {
+    let word_upper = word.to_uppercase();
+    let valid = language.words.contains(&word_upper);
+    if valid {
+        info!("Word valid ({lang}): {word_upper}");
+    } else {
+        info!("Word rejected ({lang}): {word_upper}");
+        data.rejections.record(lang, &word_upper);
+    }
+    valid
+}
+
 fn check_word_logic(
     data: &web::Data<AppState>,
     lang: &str,
     word: &str
 ) -> HttpResponse {
-    let words = match data.word_lists.get(&lang.to_lowercase()) {
-        Some(w) => w,
-        None => return HttpResponse::BadRequest().body(format!("Language '{}' not supported", lang)),
+    let lang = lang.to_lowercase();
+    let Some(language) = data.language(&lang) else {
+        return missing_language(data, &lang);
     };
-
-    let word_upper = word.to_uppercase();
-    let is_valid = words.binary_search_by(|w| w.text.cmp(&word_upper)).is_ok();
-
-    if !is_valid {
-        info!("Invalid word queried ({}): {}", lang, word_upper);
-        return HttpResponse::NotFound().finish();
-    }
-
-    info!("Valid word queried ({}): {}", lang, word_upper);
-    HttpResponse::Ok().body(format!("Valid word: {}", word_upper))
+    let resp = if lookup_word(data, &language, &lang, word) {
+        HttpResponse::Ok().body(format!("Valid word: {}", word.to_uppercase()))
+    } else {
+        HttpResponse::NotFound().finish()
+    };
+    with_lexicon_hash(&language, resp)
 }
 
 fn validate_word_logic(
@@ -29,21 +38,25 @@ fn validate_word_logic(
     lang: &str,
     word: &str
 ) -> HttpResponse {
-    let words = match data.word_lists.get(&lang.to_lowercase()) {
-        Some(w) => w,
-        None => return HttpResponse::BadRequest().finish(),
+    let lang = lang.to_lowercase();
+    let Some(language) = data.language(&lang) else {
+        return if data.is_loading(&lang) { missing_language(data, &lang) } else { HttpResponse::BadRequest().finish() };
     };
-
-    let word_upper = word.to_uppercase();
-    if words.binary_search_by(|w| w.text.cmp(&word_upper)).is_ok() {
-        info!("Word valid ({lang}): {word_upper}");
+    let resp = if lookup_word(data, &language, &lang, word) {
         HttpResponse::Ok().finish()
     } else {
-        info!("Word rejected ({lang}): {word_upper}");
         HttpResponse::NotFound().finish()
-    }
+    };
+    with_lexicon_hash(&language, resp)
 }
 
+#[utoipa::path(tag = "validation",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), ("word" = String, Path, description = "Word to look up (case-insensitive)")),
+    responses(
+        (status = 200, description = "Word is valid", body = String, content_type = "text/plain"),
+        (status = 404, description = "Word is not in the lexicon"),
+        (status = 400, description = "Language not supported"),
+    ))]
 #[get("/word/{lang}/{word}")]
 pub async fn check_word_lang(
     data: web::Data<AppState>,
@@ -54,6 +67,13 @@ pub async fn check_word_lang(
 }
 
 // Backward compatibility (default to 'en')
+#[utoipa::path(tag = "validation",
+    params(("word" = String, Path, description = "Word to look up (case-insensitive)")),
+    responses(
+        (status = 200, description = "Word is valid (English)", body = String, content_type = "text/plain"),
+        (status = 404, description = "Word is not in the lexicon"),
+        (status = 400, description = "Language not supported"),
+    ))]
 #[get("/word/{word}")]
 pub async fn check_word(
     data: web::Data<AppState>,
@@ -62,6 +82,13 @@ pub async fn check_word(
     check_word_logic(&data, "en", &word.into_inner())
 }
 
+#[utoipa::path(tag = "validation",
+    params(("lang" = String, Path, description = "Language code, e.g. en"), ("word" = String, Path, description = "Word to look up (case-insensitive)")),
+    responses(
+        (status = 200, description = "Word is valid"),
+        (status = 404, description = "Word is not in the lexicon"),
+        (status = 400, description = "Language not supported"),
+    ))]
 #[get("/validate/{lang}/{word}")]
 pub async fn validate_word_lang(
     data: web::Data<AppState>,
@@ -71,6 +98,13 @@ pub async fn validate_word_lang(
     validate_word_logic(&data, &lang, &word)
 }
 
+#[utoipa::path(tag = "validation",
+    params(("word" = String, Path, description = "Word to look up (case-insensitive)")),
+    responses(
+        (status = 200, description = "Word is valid (English)"),
+        (status = 404, description = "Word is not in the lexicon"),
+        (status = 400, description = "Language not supported"),
+    ))]
 #[get("/validate/{word}")]
 pub async fn validate_word(
     data: web::Data<AppState>,
@@ -78,3 +112,32 @@ pub async fn validate_word(
 ) -> impl Responder {
     validate_word_logic(&data, "en", &word.into_inner())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use crate::services::language::test_language;
+
+    #[actix_web::test]
+    async fn test_lang_is_case_insensitive() {
+        let mut state = AppState::for_tests(vec![("en", test_language(&["CAT"]))]);
+        state.supported_langs.push("xx".to_string());
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(state)).service(check_word_lang).service(validate_word_lang),
+        ).await;
+
+        for (uri, status) in [
+            ("/word/EN/cat", StatusCode::OK),
+            ("/validate/En/cat", StatusCode::OK),
+            ("/word/XX/cat", StatusCode::SERVICE_UNAVAILABLE),
+            ("/validate/XX/cat", StatusCode::SERVICE_UNAVAILABLE),
+            ("/word/ZZ/cat", StatusCode::BAD_REQUEST),
+            ("/validate/ZZ/cat", StatusCode::BAD_REQUEST),
+        ] {
+            let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(resp.status(), status, "{}", uri);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Daily hidden-word guessing mode. The game state lives in a signed token the client
+//! sends back with each guess. Failures use the v2 error envelope.
+
+use actix_web::{get, post, web, HttpResponse};
+use chrono::{NaiveDate, Utc};
+
+use crate::handlers::random::require_language;
+use crate::handlers::v2::error::{ApiError, ErrorCode};
+use crate::handlers::with_lexicon_hash;
+use crate::models::{AppState, LanguageData, WordleGuessRequest};
+use crate::services::wordle::{daily_target, evaluate, game_day, target_digest, GameState, WordleGame, WordleGuessResult, MAX_GUESSES, WORD_LENGTH};
+
+fn target<'a>(language: &'a LanguageData, lang: &str, day: NaiveDate) -> Result<&'a str, ApiError> {
+    daily_target(&language.wordle_targets, lang, day)
+        .map(|pos| language.words.text(pos as usize))
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "No words are eligible as a daily target", Some(lang)))
+}
+
+fn invalid(message: impl Into<String>, lang: &str) -> ApiError {
+    ApiError::new(ErrorCode::InvalidParameter, message, Some(lang))
+}
+
+#[utoipa::path(tag = "wordle",
+    params(("lang" = String, Path, description = "Language code, e.g. en")),
+    responses(
+        (status = 200, description = "A new game against today's hidden word", body = WordleGame),
+        (status = 404, description = "Language not supported, or no word is eligible as a target", body = ApiError),
+        (status = 503, description = "Language still loading", body = ApiError),
+    ))]
+#[get("/wordle/{lang}")]
+pub async fn start_game(data: web::Data<AppState>, path: web::Path<String>) -> Result<HttpResponse, ApiError> {
+    let lang = path.into_inner().to_lowercase();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+    let day = game_day(Utc::now());
+    let answer = target(&language, &lang, day)?;
+    let state = GameState { lang: lang.clone(), day, target: target_digest(&data.wordle_key, answer), guesses: Vec::new() };
+
+    let resp = HttpResponse::Ok().json(WordleGame {
+        language: lang.clone(),
+        day: state.day.to_string(),
+        length: WORD_LENGTH,
+        max_guesses: MAX_GUESSES,
+        token: state.sign(&data.wordle_key),
+    });
+    Ok(with_lexicon_hash(&language, resp))
+}
+
+#[utoipa::path(tag = "wordle",
+    params(("lang" = String, Path, description = "Language code, e.g. en")),
+    request_body = WordleGuessRequest,
+    responses(
+        (status = 200, description = "Per-letter feedback and the token for the next guess", body = WordleGuessResult),
+        (status = 400, description = "Bad token, finished game, a game whose word list has since changed, or a guess that isn't a word of the right length", body = ApiError),
+        (status = 404, description = "Language not supported", body = ApiError),
+        (status = 503, description = "Language still loading", body = ApiError),
+    ))]
+#[post("/wordle/{lang}/guess")]
+pub async fn guess(
+    data: web::Data<AppState>,
+    path: web::Path<String>,
+    body: web::Json<WordleGuessRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let lang = path.into_inner().to_lowercase();
+    let body = body.into_inner();
+    let language = require_language(&data, &lang).map_err(|e| ApiError::from_query(e, &lang))?;
+
+    let mut state = GameState::verify(&data.wordle_key, &body.token)
+        .ok_or_else(|| invalid("Invalid or altered game token", &lang))?;
+    if state.lang != lang {
+        return Err(invalid(format!("Token belongs to a '{}' game", state.lang), &lang));
+    }
+    let answer = target(&language, &lang, state.day)?;
+    if state.target != target_digest(&data.wordle_key, answer) {
+        return Err(invalid("The word list changed since this game started; start a new game", &lang));
+    }
+    if state.guesses.len() >= MAX_GUESSES || state.guesses.iter().any(|g| g == answer) {
+        return Err(invalid("This game is already over", &lang));
+    }
+
+    let guess = body.guess.trim().to_uppercase();
+    if guess.chars().count() != WORD_LENGTH {
+        return Err(invalid(format!("Guesses must have {} letters", WORD_LENGTH), &lang));
+    }
+    if !language.words.contains(&guess) {
+        return Err(invalid(format!("'{}' is not a valid word", guess), &lang));
+    }
+
+    let feedback = evaluate(answer, &guess);
+    let solved = guess == answer;
+    state.guesses.push(guess.clone());
+    let over = solved || state.guesses.len() >= MAX_GUESSES;
+
+    let resp = HttpResponse::Ok().json(WordleGuessResult {
+        language: lang.clone(),
+        day: state.day.to_string(),
+        guess,
+        feedback,
+        solved,
+        guesses_used: state.guesses.len(),
+        guesses_left: MAX_GUESSES - state.guesses.len(),
+        answer: over.then(|| answer.to_string()),
+        token: state.sign(&data.wordle_key),
+    });
+    Ok(with_lexicon_hash(&language, resp))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::handlers;
+    use crate::services::language::test_language;
+
+    #[actix_web::test]
+    async fn test_games_are_bound_to_their_word() {
+        let state = AppState::for_tests(vec![("en", test_language(&["CRANE", "TRACE", "REACT"]))]);
+        let key = state.wordle_key.clone();
+        let app = test::init_service(App::new().app_data(web::Data::new(state)).configure(handlers::configure)).await;
+
+        let game: Value = test::call_and_read_body_json(&app, test::TestRequest::get().uri("/wordle/en").to_request()).await;
+        let token = game["token"].as_str().unwrap();
+        let state = GameState::verify(&key, token).unwrap();
+        assert_eq!(state.day, game_day(Utc::now()));
+
+        let play = |token: String| test::TestRequest::post()
+            .uri("/wordle/en/guess")
+            .set_json(json!({"token": token, "guess": "crane"}))
+            .to_request();
+        assert_eq!(test::call_service(&app, play(token.to_string())).await.status(), StatusCode::OK);
+
+        // A token minted for another word, as after a reload that changed the day's target
+        let stale = GameState { target: target_digest(&key, "OTHER"), ..state };
+        assert_eq!(test::call_service(&app, play(stale.sign(&key))).await.status(), StatusCode::BAD_REQUEST);
+    }
+}
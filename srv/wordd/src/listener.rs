@@ -0,0 +1,100 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixStream;
+
+/// Where an HTTP server accepts connections: a TCP address, or a Unix domain
+/// socket given as `unix:/path/to/socket`
+#[derive(Debug, Clone)]
+pub enum Listener {
+    Tcp(String),
+    Unix(String),
+}
+
+impl Listener {
+    pub fn parse(spec: &str) -> Listener {
+        match spec.strip_prefix("unix:") {
+            Some(path) => Listener::Unix(path.to_string()),
+            None => Listener::Tcp(spec.to_string()),
+        }
+    }
+
+    /// Remove a socket file left behind by a previous run, which would otherwise
+    /// make binding fail. A socket something still accepts connections on, and
+    /// anything that isn't a socket, is left alone.
+    pub fn clear_stale_socket(&self) -> io::Result<()> {
+        if let Listener::Unix(path) = self {
+            match fs::symlink_metadata(path) {
+                Ok(meta) if meta.file_type().is_socket() => match UnixStream::connect(path) {
+                    Ok(_) => return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        format!("another process is listening on {}", path),
+                    )),
+                    Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path)?,
+                    Err(e) => return Err(e),
+                },
+                Ok(_) => return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path),
+                )),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Listener {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Listener::Tcp(addr) => write!(f, "{}", addr),
+            Listener::Unix(path) => write!(f, "unix:{}", path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse() {
+        assert!(matches!(Listener::parse("0.0.0.0:2345"), Listener::Tcp(addr) if addr == "0.0.0.0:2345"));
+        assert!(matches!(Listener::parse("unix:/run/wordd.sock"), Listener::Unix(path) if path == "/run/wordd.sock"));
+        assert!(matches!(Listener::parse("[::1]:2345"), Listener::Tcp(_)));
+        for spec in ["127.0.0.1:2345", "unix:/tmp/w.sock"] {
+            assert_eq!(Listener::parse(spec).to_string(), spec);
+        }
+    }
+
+    #[test]
+    fn test_clear_stale_socket() {
+        let dir = std::env::temp_dir().join(format!("wordd-listener-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wordd.sock");
+        let listener = Listener::Unix(path.to_str().unwrap().to_string());
+
+        // Nothing there yet
+        listener.clear_stale_socket().unwrap();
+
+        // A live socket is kept and reported
+        let live = UnixListener::bind(&path).unwrap();
+        assert_eq!(listener.clear_stale_socket().unwrap_err().kind(), io::ErrorKind::AddrInUse);
+        assert!(path.exists());
+
+        // Once its owner is gone the file is stale and removed
+        drop(live);
+        listener.clear_stale_socket().unwrap();
+        assert!(!Path::new(&path).exists());
+
+        fs::write(&path, "not a socket").unwrap();
+        assert_eq!(listener.clear_stale_socket().unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        fs::remove_dir_all(&dir).unwrap();
+
+        Listener::Tcp("127.0.0.1:0".to_string()).clear_stale_socket().unwrap();
+    }
+}
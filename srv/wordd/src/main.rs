@@ -2,15 +2,25 @@ mod models;
 mod utils;
 mod services;
 mod handlers;
+mod commands;
+mod grpc;
+mod listener;
 
 use actix_web::{web, App, HttpServer};
-use std::collections::HashMap;
-use clap::{Command, Arg};
-use log::info;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use clap::{Command, Arg, ArgAction};
+use log::{info, warn};
 use std::fs::OpenOptions;
 
-use models::AppState;
-use services::{word_loader, distribution, letter_classifier};
+use listener::Listener;
+use models::{AppState, LanguageData};
+use services::language;
+use services::budget::QueryLimits;
+use services::cache::RackCache;
+use services::compute::ComputePool;
+use services::rejections::RejectionLog;
 
 // Function to initialize logging
 fn init_logging(log_file: Option<&String>) {
@@ -40,8 +50,24 @@ async fn main() -> std::io::Result<()> {
             Arg::new("listen-host")
                 .long("listen-host")
                 .num_args(1)
+                .action(ArgAction::Append)
+                .value_delimiter(',')
                 .default_value("0.0.0.0:2345")
-                .help("Specify the listen address (e.g., 0.0.0.0:2345)"),
+                .help("Public API listen addresses, TCP or unix:/path (repeatable, e.g., 0.0.0.0:2345,unix:/run/wordd.sock)"),
+        )
+        .arg(
+            Arg::new("admin-listen")
+                .long("admin-listen")
+                .num_args(1)
+                .action(ArgAction::Append)
+                .value_delimiter(',')
+                .help("Admin listen addresses, TCP or unix:/path; reload, cache stats, explain, and the rejections report are only served here, so without one they aren't served at all"),
+        )
+        .arg(
+            Arg::new("grpc-host")
+                .long("grpc-host")
+                .num_args(1)
+                .help("Also serve the gRPC API on this address (e.g., 0.0.0.0:2346)"),
         )
         .arg(
             Arg::new("log-file")
@@ -53,6 +79,7 @@ async fn main() -> std::io::Result<()> {
             Arg::new("share-dir")
                 .long("share-dir")
                 .num_args(1)
+                .global(true)
                 .default_value("./share")
                 .help("Directory containing the word files"),
         )
@@ -63,11 +90,26 @@ async fn main() -> std::io::Result<()> {
                 .default_value("en,es,fr")
                 .help("Comma-separated list of languages to support"),
         )
+        .arg(
+            Arg::new("lazy-langs")
+                .long("lazy-langs")
+                .num_args(1)
+                .help("Comma-separated languages from --langs to load after startup instead of before"),
+        )
+        .arg(
+            Arg::new("lazy-load")
+                .long("lazy-load")
+                .num_args(1)
+                .value_parser(["background", "on-demand"])
+                .default_value("background")
+                .help("Load lazy languages in the background once listening, or on their first request"),
+        )
         .arg(
             Arg::new("total-tiles")
                 .long("total-tiles")
                 .num_args(1)
                 .default_value("100")
+                .global(true)
                 .help("Total size of the tile bag (including 2 blanks)"),
         )
         .arg(
@@ -75,16 +117,166 @@ async fn main() -> std::io::Result<()> {
                 .long("rack-size")
                 .env("DEFAULT_RANDOM_WORD_LETTER_COUNT")
                 .num_args(1)
+                .global(true)
                 .default_value("7")
                 .help("Maximum word length (rack size)"),
         )
+        .arg(
+            Arg::new("rejections-file")
+                .long("rejections-file")
+                .num_args(1)
+                .help("Persist rejected-word counts to this JSON file across restarts"),
+        )
+        .arg(
+            Arg::new("rejections-retention-days")
+                .long("rejections-retention-days")
+                .num_args(1)
+                .default_value("30")
+                .help("How many days of rejected-word counts to keep"),
+        )
+        .arg(
+            Arg::new("rejections-max-words")
+                .long("rejections-max-words")
+                .num_args(1)
+                .default_value("10000")
+                .help("Distinct rejected words tracked per language; the least rejected make room"),
+        )
+        .arg(
+            Arg::new("max-results")
+                .long("max-results")
+                .num_args(1)
+                .default_value("5000")
+                .help("Cap on words returned by one rack query page"),
+        )
+        .arg(
+            Arg::new("query-timeout-ms")
+                .long("query-timeout-ms")
+                .num_args(1)
+                .default_value("250")
+                .help("Cap on wall time for one lexicon scan or rack query, in milliseconds"),
+        )
+        .arg(
+            Arg::new("max-rack-len")
+                .long("max-rack-len")
+                .num_args(1)
+                .default_value("32")
+                .help("Longest rack, blanks included, a query may use"),
+        )
+        .arg(
+            Arg::new("compute-threads")
+                .long("compute-threads")
+                .num_args(1)
+                .help("Threads for CPU-heavy rack queries (default: available CPUs)"),
+        )
+        .arg(
+            Arg::new("compute-queue")
+                .long("compute-queue")
+                .num_args(1)
+                .default_value("64")
+                .help("Rack queries allowed to wait for a compute thread; more get 503"),
+        )
+        .arg(
+            Arg::new("cache-entries")
+                .long("cache-entries")
+                .num_args(1)
+                .default_value("4096")
+                .help("Rack query answers kept in the result cache (0 disables it)"),
+        )
+        .arg(
+            Arg::new("cache-max-positions")
+                .long("cache-max-positions")
+                .num_args(1)
+                .default_value("2000000")
+                .help("Word positions (4 bytes each) the result cache may hold across all answers"),
+        )
+        .arg(
+            Arg::new("cache-ttl-secs")
+                .long("cache-ttl-secs")
+                .num_args(1)
+                .default_value("300")
+                .help("How long a cached rack query answer stays fresh, in seconds"),
+        )
+        .arg(
+            Arg::new("wordle-secret")
+                .long("wordle-secret")
+                .env("WORDD_WORDLE_SECRET")
+                .num_args(1)
+                .hide_env_values(true)
+                .help("Key for signing guessing-game tokens; share it across instances (default: random per process)"),
+        )
+        .subcommand(
+            Command::new("import-hunspell")
+                .about("Expand a Hunspell dictionary into <share-dir>/words/<lang>/lexicon.txt")
+                .long_about(
+                    "Expand a Hunspell dictionary into <share-dir>/words/<lang>/lexicon.txt\n\n\
+                     Each word gets one level of prefixes and suffixes. Continuation classes \
+                     (affixes that carry their own flags, e.g. SFX A 0 s/B) are not supported: \
+                     their flags are dropped, so forms that need a second affix are not generated. \
+                     Compounding rules are ignored as well.",
+                )
+                .arg(Arg::new("dic").required(true).help("Hunspell .dic file"))
+                .arg(Arg::new("aff").required(true).help("Hunspell .aff file"))
+                .arg(
+                    Arg::new("lang")
+                        .long("lang")
+                        .num_args(1)
+                        .help("Language code (defaults to the .dic file name prefix, e.g. en_US -> en)"),
+                )
+                .arg(
+                    Arg::new("merge")
+                        .long("merge")
+                        .action(ArgAction::SetTrue)
+                        .help("Merge with the existing lexicon instead of replacing it"),
+                ),
+        )
+        .subcommand(
+            Command::new("lint")
+                .about("Check every language's word files and exit non-zero on errors")
+                .arg(Arg::new("dir").required(true).help("Share directory to lint")),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare the loaded lexicons, tile bags, and letter classes of two share directories")
+                .arg(Arg::new("old").required(true).help("Share directory before the change"))
+                .arg(Arg::new("new").required(true).help("Share directory after the change"))
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .num_args(1)
+                        .default_value("100")
+                        .help("Maximum added/removed words to list per language (0 for all)"),
+                ),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Time rack queries by linear scan, alphagram index, and automaton, and check the results agree")
+                .arg(
+                    Arg::new("racks")
+                        .long("racks")
+                        .num_args(1)
+                        .default_value("200")
+                        .help("Random racks to draw per language"),
+                )
+                .arg(
+                    Arg::new("blanks")
+                        .long("blanks")
+                        .num_args(1)
+                        .help("Force this many blanks into every rack (default: whatever the bag deals)"),
+                ),
+        )
         .get_matches();
 
 
-    let listen_host = matches
-        .get_one::<String>("listen-host")
+    let public_listeners: Vec<Listener> = matches
+        .get_many::<String>("listen-host")
         .expect("listen-host argument must always have a default value")
-        .clone();
+        .map(|spec| Listener::parse(spec))
+        .collect();
+    let admin_listeners: Vec<Listener> = matches
+        .get_many::<String>("admin-listen")
+        .map(|specs| specs.map(|spec| Listener::parse(spec)).collect())
+        .unwrap_or_default();
+    let grpc_host = matches.get_one::<String>("grpc-host");
     let log_file = matches.get_one::<String>("log-file");
     let share_dir = matches.get_one::<String>("share-dir").unwrap();
     let langs_str = matches.get_one::<String>("langs").unwrap();
@@ -98,69 +290,211 @@ async fn main() -> std::io::Result<()> {
         .unwrap()
         .parse::<usize>()
         .unwrap_or(7);
+    let rejections_file = matches.get_one::<String>("rejections-file").cloned();
+    let rejections_retention_days = matches
+        .get_one::<String>("rejections-retention-days")
+        .unwrap()
+        .parse::<i64>()
+        .unwrap_or(30);
+    let rejections_max_words = matches
+        .get_one::<String>("rejections-max-words")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or(10_000);
+
+    let max_results = matches
+        .get_one::<String>("max-results")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or(5000);
+    let query_timeout_ms = matches
+        .get_one::<String>("query-timeout-ms")
+        .unwrap()
+        .parse::<u64>()
+        .unwrap_or(250);
+    let max_rack_len = matches
+        .get_one::<String>("max-rack-len")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or(32);
+    let compute_threads = matches
+        .get_one::<String>("compute-threads")
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2));
+    let compute_queue = matches
+        .get_one::<String>("compute-queue")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or(64);
+    let cache_entries = matches
+        .get_one::<String>("cache-entries")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or(4096);
+    let cache_max_positions = matches
+        .get_one::<String>("cache-max-positions")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or(2_000_000);
+    let cache_ttl_secs = matches
+        .get_one::<String>("cache-ttl-secs")
+        .unwrap()
+        .parse::<u64>()
+        .unwrap_or(300);
+    let wordle_secret = matches.get_one::<String>("wordle-secret").cloned();
 
     init_logging(log_file);
 
-    let mut word_lists = HashMap::new();
-    let mut supported_langs = Vec::new();
-    let mut tile_bags = HashMap::new();
-    let mut vowel_sets = HashMap::new();
-    let mut consonant_sets = HashMap::new();
-    let mut unicorn_sets = HashMap::new();
-
-    for lang in langs_str.split(',') {
-        let lang = lang.trim().to_lowercase();
-        info!("Loading word list for language: {} (max_len: {})", lang, rack_size);
-        let words = word_loader::load_filtered_words(share_dir, &lang, rack_size);
-        
-        // Calculate letter distribution directly from the filtered in-memory set
-        let freq = distribution::calculate_distribution_from_set(&words);
-        info!("Calculated letter distribution for {} ({} unique letters, from {} words)", lang, freq.len(), words.len());
-        
-        // Compute tile bag
-        let bag = distribution::compute_tile_bag(&freq, total_tiles);
-        info!("Computed tile bag for {} ({} total tiles)", lang, bag.values().sum::<usize>());
-        
-        // Classify letters
-        let (vowels, consonants, unicorns) = letter_classifier::classify_letters(&freq, &lang);
-        info!("Classified letters for {}: {} vowels, {} consonants, {} unicorns", 
-              lang, vowels.len(), consonants.len(), unicorns.len());
-        
-        // Store all pre-computed data
-        word_lists.insert(lang.clone(), words);
-        supported_langs.push(lang.clone());
-        tile_bags.insert(lang.clone(), bag);
-        vowel_sets.insert(lang.clone(), vowels);
-        consonant_sets.insert(lang.clone(), consonants);
-        unicorn_sets.insert(lang.clone(), unicorns);
+    match matches.subcommand() {
+        Some(("import-hunspell", sub)) => {
+            return commands::import_hunspell::run(
+                share_dir,
+                sub.get_one::<String>("dic").unwrap(),
+                sub.get_one::<String>("aff").unwrap(),
+                sub.get_one::<String>("lang").map(String::as_str),
+                sub.get_flag("merge"),
+            );
+        }
+        Some(("lint", sub)) => {
+            let clean = commands::lint::run(sub.get_one::<String>("dir").unwrap(), rack_size)?;
+            std::process::exit(if clean { 0 } else { 1 });
+        }
+        Some(("diff", sub)) => {
+            let limit = sub.get_one::<String>("limit").unwrap().parse::<usize>().unwrap_or(100);
+            return commands::diff::run(
+                sub.get_one::<String>("old").unwrap(),
+                sub.get_one::<String>("new").unwrap(),
+                rack_size,
+                total_tiles,
+                limit,
+            );
+        }
+        Some(("bench", sub)) => {
+            let racks = sub.get_one::<String>("racks").unwrap().parse::<usize>().unwrap_or(200);
+            let blanks = sub.get_one::<String>("blanks").and_then(|b| b.parse::<usize>().ok());
+            let identical = commands::bench::run(share_dir, rack_size, total_tiles, racks, blanks)?;
+            std::process::exit(if identical { 0 } else { 1 });
+        }
+        _ => {}
+    }
+
+    let supported_langs: Vec<String> = langs_str.split(',').map(|l| l.trim().to_lowercase()).collect();
+    let lazy_langs: HashSet<String> = matches
+        .get_one::<String>("lazy-langs")
+        .map(|l| l.split(',').map(|l| l.trim().to_lowercase()).collect())
+        .unwrap_or_default();
+    for lang in lazy_langs.iter().filter(|l| !supported_langs.contains(l)) {
+        warn!("Ignoring lazy language '{}': not in --langs", lang);
     }
+    let on_demand = matches.get_one::<String>("lazy-load").map(String::as_str) == Some("on-demand");
+
+    // Each language loads independently, so load them all at once
+    let languages: HashMap<String, Arc<LanguageData>> = std::thread::scope(|scope| {
+        let loads: Vec<_> = supported_langs
+            .iter()
+            .filter(|lang| !lazy_langs.contains(*lang))
+            .map(|lang| {
+                let handle = scope.spawn(move || language::load_language(share_dir, lang, rack_size, total_tiles));
+                (lang.clone(), handle)
+            })
+            .collect();
+        loads
+            .into_iter()
+            .map(|(lang, handle)| (lang, Arc::new(handle.join().expect("language load panicked"))))
+            .collect()
+    });
+    let lazy_langs: Vec<String> = supported_langs.iter().filter(|l| lazy_langs.contains(*l)).cloned().collect();
+
+    let rejections = Arc::new(RejectionLog::open(rejections_file, rejections_retention_days, rack_size, rejections_max_words));
+
+    let wordle_key = match wordle_secret {
+        Some(secret) => secret.into_bytes(),
+        None => {
+            warn!("No --wordle-secret set; guessing-game tokens won't survive a restart or work across instances");
+            rand::random::<[u8; 32]>().to_vec()
+        }
+    };
 
     let state = AppState {
-        word_lists,
+        languages: Arc::new(RwLock::new(languages)),
+        loading: Arc::new(Mutex::new(HashSet::new())),
+        on_demand: Arc::new(Mutex::new(if on_demand { lazy_langs.iter().cloned().collect() } else { HashSet::new() })),
         supported_langs,
-        tile_bags,
-        vowel_sets,
-        consonant_sets,
-        unicorn_sets,
+        rejections: rejections.clone(),
+        share_dir: share_dir.clone(),
+        max_word_len: rack_size,
+        total_tiles,
+        query_limits: QueryLimits::new(max_results, Duration::from_millis(query_timeout_ms), max_rack_len),
+        compute: ComputePool::new(compute_threads, compute_queue),
+        rack_cache: RackCache::new(cache_entries, cache_max_positions, Duration::from_secs(cache_ttl_secs)),
+        wordle_key,
     };
     let shared_state = web::Data::new(state);
 
-    HttpServer::new(move || {
+    if let Some(grpc_host) = grpc_host {
+        let addr = grpc_host.parse().map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid --grpc-host '{}': {}", grpc_host, e))
+        })?;
+        grpc::spawn(addr, shared_state.clone().into_inner())?;
+    }
+
+    // Periodically prune and persist rejected-word counts
+    let flush_log = rejections.clone();
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = flush_log.save() {
+                warn!("Failed to save rejection log: {}", e);
+            }
+        }
+    });
+
+    let public_state = shared_state.clone();
+    let mut public_server = HttpServer::new(move || {
         App::new()
-            .app_data(shared_state.clone())
-            .service(handlers::config::get_langs)
-            .service(handlers::config::get_config)
-            .service(handlers::validation::check_word_lang)
-            .service(handlers::validation::check_word)
-            .service(handlers::validation::validate_word_lang)
-            .service(handlers::validation::validate_word)
-            .service(handlers::random::rand_letter)
-            .service(handlers::random::rand_vowel)
-            .service(handlers::random::rand_consonant)
-            .service(handlers::random::rand_unicorn)
-            .service(handlers::random::rand_word)
-    })
-    .bind(&listen_host)?
-    .run()
-    .await
+            .app_data(public_state.clone())
+            .configure(handlers::configure)
+    });
+    for listener in &public_listeners {
+        listener.clear_stale_socket()?;
+        public_server = match listener {
+            Listener::Tcp(addr) => public_server.bind(addr)?,
+            Listener::Unix(path) => public_server.bind_uds(path)?,
+        };
+        info!("Public API listening on {}", listener);
+    }
+
+    if !on_demand {
+        for lang in &lazy_langs {
+            shared_state.load_in_background(lang);
+        }
+    }
+
+    if admin_listeners.is_empty() {
+        info!("No --admin-listen set; reload, cache stats, explain, and the rejections report are not served");
+        public_server.run().await?;
+    } else {
+        let admin_state = shared_state.clone();
+        let mut admin_server = HttpServer::new(move || {
+            App::new()
+                .app_data(admin_state.clone())
+                .configure(handlers::admin::configure)
+        })
+        .workers(1);
+        for listener in &admin_listeners {
+            listener.clear_stale_socket()?;
+            admin_server = match listener {
+                Listener::Tcp(addr) => admin_server.bind(addr)?,
+                Listener::Unix(path) => admin_server.bind_uds(path)?,
+            };
+            info!("Admin API listening on {}", listener);
+        }
+        tokio::try_join!(public_server.run(), admin_server.run())?;
+    }
+
+    if let Err(e) = rejections.save() {
+        warn!("Failed to save rejection log: {}", e);
+    }
+    Ok(())
 }
@@ -1,5 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use log::{error, info};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::services::alphagram::AlphagramIndex;
+use crate::services::budget::QueryLimits;
+use crate::services::cache::RackCache;
+use crate::services::compute::ComputePool;
+use crate::services::ladder::{LadderDifficulty, LadderIndex};
+use crate::services::lexicon::{Lexicon, LexiconMemory};
+use crate::services::likelihood::Commonness;
+use crate::services::rejections::RejectionLog;
+use crate::services::word_loader::PipelineInputs;
 
 #[derive(Serialize, Clone, Debug)]
 pub struct Word {
@@ -8,36 +21,419 @@ pub struct Word {
     pub len: usize,
 }
 
+/// Everything the load pipeline derives from one language's word files
+pub struct LanguageData {
+    pub hash: String,
+    pub words: Lexicon,
+    pub tile_bag: HashMap<char, usize>,
+    pub vowels: Vec<char>,
+    pub consonants: Vec<char>,
+    pub unicorns: Vec<char>,
+    pub inputs: PipelineInputs,
+    pub index: AlphagramIndex,
+    pub commonness: Commonness,
+    pub ladders: LadderIndex,
+    /// Positions of the words a daily guessing game may hide
+    pub wordle_targets: Vec<u32>,
+}
+
 /// Application state shared across all handlers
-#[derive(Clone)]
 pub struct AppState {
-    pub word_lists: HashMap<String, Vec<Word>>,
+    /// Read through `language()` so each request keeps one consistent snapshot
+    pub languages: Arc<RwLock<HashMap<String, Arc<LanguageData>>>>,
+    /// Languages with a load running in the background
+    pub loading: Arc<Mutex<HashSet<String>>>,
+    /// Lazy languages that load on their first request and haven't had one yet, or
+    /// whose load failed and should be retried
+    pub on_demand: Arc<Mutex<HashSet<String>>>,
     pub supported_langs: Vec<String>,
-    pub tile_bags: HashMap<String, HashMap<char, usize>>,
-    pub vowel_sets: HashMap<String, Vec<char>>,
-    pub consonant_sets: HashMap<String, Vec<char>>,
-    pub unicorn_sets: HashMap<String, Vec<char>>,
+    pub rejections: Arc<RejectionLog>,
+    pub share_dir: String,
+    pub max_word_len: usize,
+    pub total_tiles: usize,
+    pub query_limits: QueryLimits,
+    pub compute: ComputePool,
+    pub rack_cache: RackCache,
+    /// Signs guessing-game tokens
+    pub wordle_key: Vec<u8>,
+}
+
+impl AppState {
+    /// The loaded data for a language. Callers hold their own reference, so a
+    /// concurrent reload never changes the data out from under a request.
+    /// An unloaded on-demand language starts loading in the background and is
+    /// reported as loading until it's ready.
+    pub fn language(&self, lang: &str) -> Option<Arc<LanguageData>> {
+        let found = self.loaded(lang);
+        if found.is_none() && self.on_demand.lock().unwrap().remove(lang) {
+            self.load_in_background(lang);
+        }
+        found
+    }
+
+    /// Like `language()`, but never triggers an on-demand load
+    pub fn loaded(&self, lang: &str) -> Option<Arc<LanguageData>> {
+        self.languages.read().unwrap().get(lang).cloned()
+    }
+
+    /// Supported but not yet usable: loading now, or waiting for its first request
+    pub fn is_loading(&self, lang: &str) -> bool {
+        self.supported_langs.iter().any(|l| l == lang) && self.loaded(lang).is_none()
+    }
+
+    /// Load a language on its own thread and publish it when done
+    pub fn load_in_background(&self, lang: &str) {
+        let (share_dir, max_word_len, total_tiles) = (self.share_dir.clone(), self.max_word_len, self.total_tiles);
+        let loader_lang = lang.to_string();
+        self.spawn_load(lang, move || {
+            crate::services::language::load_language(&share_dir, &loader_lang, max_word_len, total_tiles)
+        });
+    }
+
+    /// Run `load` on its own thread unless `lang` is already loading. If it panics, the
+    /// language goes back to on-demand so its next request tries again.
+    fn spawn_load(&self, lang: &str, load: impl FnOnce() -> LanguageData + Send + 'static) {
+        if !self.loading.lock().unwrap().insert(lang.to_string()) {
+            return;
+        }
+        let languages = Arc::clone(&self.languages);
+        let loading = Arc::clone(&self.loading);
+        let on_demand = Arc::clone(&self.on_demand);
+        let loader_lang = lang.to_string();
+        let spawned = std::thread::Builder::new()
+            .name(format!("wordd-load-{}", lang))
+            .spawn(move || {
+                let lang = loader_lang;
+                match std::panic::catch_unwind(std::panic::AssertUnwindSafe(load)) {
+                    Ok(data) => {
+                        info!("Loaded {} in the background: {} words", lang, data.words.len());
+                        languages.write().unwrap().entry(lang.clone()).or_insert_with(|| Arc::new(data));
+                    }
+                    Err(_) => {
+                        error!("Loading {} panicked; will retry on its next request", lang);
+                        on_demand.lock().unwrap().insert(lang.clone());
+                    }
+                }
+                loading.lock().unwrap().remove(&lang);
+            });
+        if let Err(e) = spawned {
+            error!("Failed to spawn loader thread: {}", e);
+            self.loading.lock().unwrap().remove(lang);
+        }
+    }
+
+    /// Swap in freshly loaded data for a language, dropping its cached rack answers
+    pub fn replace_language(&self, lang: &str, data: LanguageData) {
+        self.languages.write().unwrap().insert(lang.to_string(), Arc::new(data));
+        self.rack_cache.invalidate(lang);
+    }
+}
+
+#[cfg(test)]
+impl AppState {
+    /// State serving the given languages, with default limits and no share directory
+    pub fn for_tests(languages: Vec<(&str, LanguageData)>) -> Self {
+        use std::time::Duration;
+
+        AppState {
+            supported_langs: languages.iter().map(|(lang, _)| lang.to_string()).collect(),
+            languages: Arc::new(RwLock::new(
+                languages.into_iter().map(|(lang, data)| (lang.to_string(), Arc::new(data))).collect(),
+            )),
+            loading: Arc::new(Mutex::new(HashSet::new())),
+            on_demand: Arc::new(Mutex::new(HashSet::new())),
+            rejections: Arc::new(RejectionLog::new(None, 30, 7, 100)),
+            share_dir: String::new(),
+            max_word_len: 7,
+            total_tiles: 100,
+            query_limits: QueryLimits::new(1000, Duration::from_secs(5), 16),
+            compute: ComputePool::new(1, 16),
+            rack_cache: RackCache::new(16, 10_000, Duration::from_secs(60)),
+            wordle_key: b"test".to_vec(),
+        }
+    }
+}
+
+/// Whether a language can serve requests yet
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LangStatus {
+    Ready,
+    Loading,
+}
+
+impl LangStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LangStatus::Ready => "ready",
+            LangStatus::Loading => "loading",
+        }
+    }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct LangInfo {
     pub name: String,
     pub code: String,
+    /// Empty while the language is loading
+    pub lexicon_hash: String,
+    pub status: LangStatus,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ConfigResponse {
+    /// Tile counts per letter, `_` being the blank
     pub tiles: HashMap<char, usize>,
+    /// The rarest letters with their bonus value
     pub unicorns: HashMap<char, usize>,
     pub vowels: Vec<char>,
     pub bag: HashMap<char, usize>,
     pub word_count: usize,
+    /// Content hash of the loaded words, bag, and letter classes
+    pub lexicon_hash: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, ToSchema)]
+pub struct LangsResponse {
+    pub languages: Vec<LangInfo>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ValidationResponse {
+    pub language: String,
+    pub word: String,
+    pub valid: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LettersResponse {
+    pub language: String,
+    pub letters: Vec<char>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WordsResponse {
+    pub language: String,
+    pub words: Vec<String>,
+    /// True if the time budget cut the candidate scan short
+    pub truncated: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SolveResponse {
+    pub language: String,
+    pub letters: String,
+    /// Longest first, then alphabetical, continuing across pages
+    pub words: Vec<String>,
+    /// True if more words follow on later pages
+    pub truncated: bool,
+    /// Pass as `cursor` to continue where this page stopped
+    pub next_cursor: Option<String>,
+    /// With `likelihood=true`: chance (0 to 1) that another player also finds each word, parallel to `words`
+    pub duplicate_likelihood: Option<Vec<f32>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PrefixResponse {
+    pub language: String,
+    pub prefix: String,
+    /// Alphabetical
+    pub words: Vec<String>,
+    /// True if more words share the prefix than `limit` allowed
+    pub truncated: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LanguageMemory {
+    pub language: String,
+    pub word_count: usize,
+    pub lexicon: LexiconMemory,
+    /// Approximate size of the alphagram index
+    pub index_bytes: usize,
+    /// Approximate size of the word ladder neighbour index
+    pub ladder_bytes: usize,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReloadResponse {
+    pub language: String,
+    pub lexicon_hash: String,
+    /// Hash of the data that was replaced, if the language was loaded before
+    pub previous_hash: Option<String>,
+    pub word_count: usize,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SolveQuery {
+    /// Rack to form words from; `_` is a blank that matches any letter
+    pub letters: String,
+    /// Maximum words per page (default and cap: the server's --max-results)
+    pub limit: Option<usize>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    /// Also estimate how likely each word is to be played by someone else too
+    pub likelihood: Option<bool>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PrefixQuery {
+    /// Maximum words to return (default 100, capped by the server's --max-results)
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RackAnalyzeQuery {
+    /// Rack to analyze; `_` is a blank
+    pub rack: String,
+    /// Letter values as `A:1,B:3,...` (default: the language's standard values, no daily bonus)
+    pub values: Option<String>,
+    /// Vowels a balanced rack needs (default 1)
+    pub min_vowels: Option<usize>,
+    /// Consonants a balanced rack needs (default 1)
+    pub min_consonants: Option<usize>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PlayedWord {
+    pub player: String,
+    pub word: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RoundAnalysisRequest {
+    /// The round's rack; `_` is a blank
+    pub rack: String,
+    /// The game's letter values (default: the language's standard values, no daily bonus)
+    pub values: Option<HashMap<char, u32>>,
+    #[serde(default)]
+    pub plays: Vec<PlayedWord>,
+    /// How many top words to list (default 10, capped by the server's --max-results)
+    pub top: Option<usize>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HintQuery {
+    /// Rack to hint at; `_` is a blank
+    pub rack: String,
+    /// 1: length, 2: first letter, 3: masked pattern, 4: the word (default 1)
+    pub level: Option<u8>,
+    /// Letter values as `A:1,B:3,...` (default: the language's standard values, no daily bonus)
+    pub values: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LadderQuery {
+    /// First word of the ladder (case-insensitive)
+    pub from: String,
+    /// Last word, the same length as `from`
+    pub to: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LadderPuzzleQuery {
+    /// Letters per word (default 4)
+    pub length: Option<usize>,
+    /// easy, medium (default), or hard
+    #[param(inline)]
+    pub difficulty: Option<LadderDifficulty>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WordleGuessRequest {
+    /// Token from the game start or the previous guess
+    pub token: String,
+    pub guess: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RackQuery {
+    /// Rack to search; `_` is a blank
+    pub rack: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RandQuery {
+    /// How many results to return (default 1, capped by the server's --max-results)
     pub count: Option<usize>,
+    /// Rack the words must be formed from; `_` is a blank that matches any letter
     pub letters: Option<String>,
+    /// Minimum number of vowels in each word
     pub min_vowels: Option<usize>,
+    /// Minimum number of consonants in each word
     pub min_consonants: Option<usize>,
+    /// Time budget in milliseconds for constrained picks without `letters` (capped by the
+    /// server's --query-timeout-ms); rack picks always consider every word the rack forms
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RejectionQuery {
+    /// Unix seconds, RFC 3339 timestamp, or YYYY-MM-DD (default: everything retained)
+    pub since: Option<String>,
+    /// Maximum words per list (default 50)
+    pub limit: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use crate::services::language::test_language;
+
+    fn wait_until(what: &str, done: impl Fn() -> bool) {
+        let start = Instant::now();
+        while !done() {
+            assert!(start.elapsed() < Duration::from_secs(10), "timed out waiting until {}", what);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_lazy_language_loads_in_background() {
+        let mut state = AppState::for_tests(vec![("en", test_language(&["CAT"]))]);
+        state.supported_langs.push("xx".to_string());
+        assert!(state.is_loading("xx"));
+        assert!(!state.is_loading("en") && !state.is_loading("zz"));
+
+        state.load_in_background("xx");
+        wait_until("xx is loaded", || state.loaded("xx").is_some());
+        wait_until("the load is finished", || state.loading.lock().unwrap().is_empty());
+        assert!(!state.is_loading("xx"));
+    }
+
+    #[test]
+    fn test_on_demand_language_loads_on_first_request() {
+        let mut state = AppState::for_tests(Vec::new());
+        state.supported_langs.push("xx".to_string());
+        state.on_demand.lock().unwrap().insert("xx".to_string());
+
+        assert!(state.loaded("xx").is_none());
+        assert!(state.on_demand.lock().unwrap().contains("xx"), "loaded() must not trigger a load");
+        assert!(state.language("xx").is_none());
+        assert!(state.on_demand.lock().unwrap().is_empty());
+        wait_until("xx is loaded", || state.language("xx").is_some());
+    }
+
+    #[test]
+    fn test_panicking_load_is_retried() {
+        let mut state = AppState::for_tests(Vec::new());
+        state.supported_langs.push("xx".to_string());
+        state.spawn_load("xx", || panic!("corrupt lexicon"));
+        wait_until("the failed load is finished", || state.loading.lock().unwrap().is_empty());
+        assert!(state.loaded("xx").is_none());
+        assert!(state.on_demand.lock().unwrap().contains("xx"));
+
+        // The next request starts a fresh load, which succeeds
+        assert!(state.language("xx").is_none());
+        wait_until("xx is loaded", || state.loaded("xx").is_some());
+    }
 }
@@ -0,0 +1,179 @@
+use fst::{Map, MapBuilder};
+
+use crate::services::lexicon::Lexicon;
+
+/// Cap on alphagrams enumerated for a rack with blanks before giving up on the index.
+/// About what one blank in a full rack costs; past that the lexicon automaton is faster.
+const EXPANSION_LIMIT: usize = 2_000;
+
+/// A word's letters, uppercased and sorted; non-letters are dropped, exactly as
+/// `contains_only_letters` ignores them
+pub fn alphagram(text: &str) -> String {
+    let mut letters: Vec<char> = text.to_uppercase().chars().filter(|c| c.is_alphabetic()).collect();
+    letters.sort_unstable();
+    letters.into_iter().collect()
+}
+
+/// Per-language anagram index over the sorted word list. An FST maps each alphagram
+/// to its group, and the groups' positions are packed back to back: group `g` is
+/// `positions[starts[g]..starts[g + 1]]`. No per-alphagram heap allocation.
+pub struct AlphagramIndex {
+    groups: Map<Vec<u8>>,
+    starts: Vec<u32>,
+    /// Word-list positions, ascending within each group
+    positions: Vec<u32>,
+    /// Every letter used by the lexicon, sorted; what a blank can stand for
+    alphabet: Vec<char>,
+}
+
+impl AlphagramIndex {
+    pub fn build(words: &Lexicon) -> Self {
+        let mut keyed: Vec<(String, u32)> = words.iter().enumerate()
+            .map(|(pos, word)| (alphagram(word), pos as u32))
+            .collect();
+        keyed.sort_unstable();
+
+        let mut builder = MapBuilder::memory();
+        let mut starts = Vec::new();
+        let mut positions = Vec::with_capacity(keyed.len());
+        let mut alphabet = Vec::new();
+        for (i, (key, pos)) in keyed.iter().enumerate() {
+            if i == 0 || keyed[i - 1].0 != *key {
+                builder.insert(key, starts.len() as u64).expect("alphagrams are sorted and unique");
+                starts.push(positions.len() as u32);
+                alphabet.extend(key.chars());
+            }
+            positions.push(*pos);
+        }
+        starts.push(positions.len() as u32);
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        AlphagramIndex { groups: builder.into_map(), starts, positions, alphabet }
+    }
+
+    /// Approximate heap bytes held by the index
+    pub fn memory(&self) -> usize {
+        self.groups.as_fst().as_bytes().len()
+            + (self.starts.capacity() + self.positions.capacity()) * 4
+            + self.alphabet.capacity() * std::mem::size_of::<char>()
+    }
+
+    /// Positions of the words spelled by exactly these letters, given as an alphagram
+    fn group(&self, key: &str) -> &[u32] {
+        match self.groups.get(key) {
+            Some(g) => &self.positions[self.starts[g as usize] as usize..self.starts[g as usize + 1] as usize],
+            None => &[],
+        }
+    }
+
+    /// Sorted positions of every word whose letters fit in `rack`, where `_` is a
+    /// blank matching any letter. Walks the rack's sub-multisets instead of the
+    /// lexicon. Racks with so many blanks that the walk passes its bound return
+    /// None; the lexicon's automaton walk answers those instead.
+    pub fn subanagrams(&self, rack: &str) -> Option<Vec<u32>> {
+        self.subanagrams_within(rack, EXPANSION_LIMIT)
+    }
+
+    fn subanagrams_within(&self, rack: &str, expansion_limit: usize) -> Option<Vec<u32>> {
+        let rack_letters = alphagram(rack);
+        let blanks = rack.chars().filter(|&c| c == '_').count();
+        let max_len = rack_letters.chars().count() + blanks;
+
+        let mut positions = Vec::new();
+        let mut walk = SubsetWalk {
+            index: self,
+            available: self.letter_counts(&rack_letters, blanks),
+            max_len,
+            limit: expansion_limit,
+            current: String::new(),
+            visited: 0,
+            found: &mut positions,
+        };
+        if !walk.visit(0, 0, blanks) {
+            return None;
+        }
+
+        positions.sort_unstable();
+        Some(positions)
+    }
+
+    /// (letter, count in rack) for every letter the walk may choose: rack letters
+    /// always, the rest of the alphabet only when there are blanks to spend
+    fn letter_counts(&self, rack_letters: &str, blanks: usize) -> Vec<(char, usize)> {
+        let mut counts: Vec<(char, usize)> = Vec::new();
+        for c in rack_letters.chars() {
+            match counts.last_mut() {
+                Some((last, n)) if *last == c => *n += 1,
+                _ => counts.push((c, 1)),
+            }
+        }
+        if blanks > 0 {
+            for &c in &self.alphabet {
+                if !counts.iter().any(|&(r, _)| r == c) {
+                    counts.push((c, 0));
+                }
+            }
+            counts.sort_unstable();
+        }
+        counts
+    }
+}
+
+/// Depth-first enumeration of the multisets a rack can spell, in sorted letter
+/// order so each one is generated once and is already its own alphagram
+struct SubsetWalk<'a> {
+    index: &'a AlphagramIndex,
+    available: Vec<(char, usize)>,
+    max_len: usize,
+    limit: usize,
+    current: String,
+    visited: usize,
+    found: &'a mut Vec<u32>,
+}
+
+impl SubsetWalk<'_> {
+    /// Returns false if the walk exceeded its limit and was abandoned
+    fn visit(&mut self, letter: usize, len: usize, blanks: usize) -> bool {
+        if letter == self.available.len() {
+            self.visited += 1;
+            self.found.extend_from_slice(self.index.group(&self.current));
+            return self.visited <= self.limit;
+        }
+
+        let (c, in_rack) = self.available[letter];
+        let mark = self.current.len();
+        let most = (in_rack + blanks).min(self.max_len - len);
+        for count in 0..=most {
+            if count > 0 {
+                self.current.push(c);
+            }
+            let spent = count.saturating_sub(in_rack);
+            if !self.visit(letter + 1, len + count, blanks - spent) {
+                return false;
+            }
+        }
+        self.current.truncate(mark);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::contains_only_letters;
+
+    #[test]
+    fn test_subanagrams_match_contains_only_letters() {
+        let lex = Lexicon::from_texts(&["AT", "TA", "CAT", "ACT", "TACT", "CART", "TRACT", "ÉTÉ", "TE", "EAT", "TEA", "ZZZ"]);
+        let index = AlphagramIndex::build(&lex);
+        for rack in ["CAT", "TACT", "CA_", "__", "T_É", "RACT_", "___", "Z", "tac"] {
+            let expected: Vec<u32> = lex.iter().enumerate()
+                .filter(|(_, w)| contains_only_letters(w, rack))
+                .map(|(i, _)| i as u32)
+                .collect();
+            assert_eq!(index.subanagrams(rack), Some(expected), "rack {}", rack);
+        }
+        assert_eq!(index.subanagrams_within("RACT_", 1), None);
+    }
+}
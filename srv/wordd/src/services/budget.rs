@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+/// Server-wide caps on rack queries that scan the lexicon
+pub struct QueryLimits {
+    pub max_results: usize,
+    pub max_time: Duration,
+    /// Longest rack, blanks included, a query may use
+    pub max_rack_len: usize,
+}
+
+impl QueryLimits {
+    pub fn new(max_results: usize, max_time: Duration, max_rack_len: usize) -> Self {
+        QueryLimits { max_results, max_time, max_rack_len }
+    }
+
+    /// A rack that's no longer than the server allows
+    pub fn check_rack(&self, rack: &str) -> Result<(), QueryError> {
+        let len = rack.chars().count();
+        if len > self.max_rack_len {
+            return Err(QueryError::InvalidRack(format!(
+                "Rack has {} tiles; at most {} are allowed", len, self.max_rack_len
+            )));
+        }
+        Ok(())
+    }
+
+    /// A per-request budget, with the requested limits clamped to the server caps.
+    /// A requested result limit of 0 means "as many as the server allows".
+    pub fn budget(&self, max_results: Option<usize>, timeout_ms: Option<u64>) -> Budget {
+        let max_results = match max_results {
+            Some(n) if n > 0 => n.min(self.max_results),
+            _ => self.max_results,
+        };
+        let max_time = timeout_ms.map(Duration::from_millis).unwrap_or(self.max_time).min(self.max_time);
+        Budget {
+            max_results,
+            deadline: Instant::now() + max_time,
+        }
+    }
+}
+
+/// Limits for a single query: stop after `max_results` matches or at `deadline`
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    pub max_results: usize,
+    pub deadline: Instant,
+}
+
+impl Budget {
+    /// No result cap and a deadline that never arrives, for offline tools
+    pub fn unlimited() -> Self {
+        Budget {
+            max_results: usize::MAX,
+            deadline: Instant::now() + Duration::from_secs(86400 * 365),
+        }
+    }
+
+    pub fn expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+/// Why a budgeted rack query couldn't run
+#[derive(Debug)]
+pub enum QueryError {
+    UnsupportedLanguage,
+    /// The language is supported but still loading
+    Loading,
+    /// The compute queue is full
+    Overloaded,
+    InvalidCursor(String),
+    /// The rack is longer than the server allows
+    InvalidRack(String),
+    /// The query ran past its deadline
+    TimedOut,
+    /// The query panicked on its compute thread
+    Failed,
+}
+
+/// Opaque resume point for a paged scan: the lexicon position to continue from,
+/// tied to the lexicon hash so a cursor can't silently skip words after a reload
+pub fn encode_cursor(lexicon_hash: &str, position: usize) -> String {
+    format!("{}.{}", &lexicon_hash[..lexicon_hash.len().min(8)], position)
+}
+
+pub fn decode_cursor(lexicon_hash: &str, cursor: &str) -> Result<usize, QueryError> {
+    let (prefix, position) = cursor
+        .split_once('.')
+        .ok_or_else(|| QueryError::InvalidCursor(format!("Malformed cursor '{}'", cursor)))?;
+    if !lexicon_hash.starts_with(prefix) || prefix.is_empty() {
+        return Err(QueryError::InvalidCursor("Cursor is from a different lexicon; restart the query".to_string()));
+    }
+    position
+        .parse()
+        .map_err(|_| QueryError::InvalidCursor(format!("Malformed cursor '{}'", cursor)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_clamps_to_server_caps() {
+        let limits = QueryLimits::new(100, Duration::from_millis(50), 4);
+        assert_eq!(limits.budget(Some(1000), None).max_results, 100);
+        assert_eq!(limits.budget(Some(10), None).max_results, 10);
+        assert_eq!(limits.budget(Some(0), None).max_results, 100);
+        assert!(limits.budget(None, Some(10_000)).deadline <= Instant::now() + Duration::from_millis(50));
+        assert!(limits.check_rack("CAT_").is_ok());
+        assert!(matches!(limits.check_rack("CATS_"), Err(QueryError::InvalidRack(_))));
+    }
+
+    #[test]
+    fn test_cursor_round_trip_and_reload() {
+        let cursor = encode_cursor("abcdef0123456789", 42);
+        assert_eq!(decode_cursor("abcdef0123456789", &cursor).unwrap(), 42);
+        assert!(decode_cursor("ffffffff01234567", &cursor).is_err());
+        assert!(decode_cursor("abcdef0123456789", "garbage").is_err());
+    }
+}
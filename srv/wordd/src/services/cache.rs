@@ -0,0 +1,276 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::services::alphagram::alphagram;
+use crate::services::generator::WordFilters;
+
+/// A rack query in canonical form: racks that differ only in letter order or case
+/// share an entry. The lexicon hash keeps a reloaded language from serving old answers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RackKey {
+    lang: String,
+    lexicon_hash: String,
+    rack: String,
+    min_vowels: Option<usize>,
+    min_consonants: Option<usize>,
+    filters: WordFilters,
+}
+
+impl RackKey {
+    /// `filters` should already be normalized so equivalent queries share an entry
+    pub fn new(
+        lang: &str,
+        lexicon_hash: &str,
+        rack: &str,
+        min_vowels: Option<usize>,
+        min_consonants: Option<usize>,
+        filters: &WordFilters,
+    ) -> Self {
+        let blanks = rack.chars().filter(|&c| c == '_').count();
+        RackKey {
+            lang: lang.to_string(),
+            lexicon_hash: lexicon_hash.to_string(),
+            rack: alphagram(rack) + &"_".repeat(blanks),
+            min_vowels,
+            min_consonants,
+            filters: filters.clone(),
+        }
+    }
+
+    /// The rack as letters then blanks, which every rack query treats the same as the original
+    pub fn rack(&self) -> &str {
+        &self.rack
+    }
+
+    pub fn min_vowels(&self) -> Option<usize> {
+        self.min_vowels
+    }
+
+    pub fn min_consonants(&self) -> Option<usize> {
+        self.min_consonants
+    }
+
+    pub fn filters(&self) -> &WordFilters {
+        &self.filters
+    }
+}
+
+struct Entry {
+    matches: Arc<Vec<u32>>,
+    inserted: Instant,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    /// Word positions held across all entries, and the most allowed
+    pub positions: usize,
+    pub max_positions: usize,
+    pub ttl_secs: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries pushed out by newer ones once the cache was full
+    pub evictions: u64,
+    /// Entries found past their TTL
+    pub expirations: u64,
+    /// Entries dropped because their language was reloaded
+    pub invalidations: u64,
+    /// Answers too large to cache at all
+    pub oversized: u64,
+    pub hit_rate: f64,
+}
+
+/// LRU of complete rack query answers (lexicon positions of every matching word),
+/// so the repeated queries a game round makes for one rack skip the compute pool.
+/// Bounded by entry count and by the positions held across all entries, since one
+/// many-blank answer can outweigh thousands of ordinary ones. A capacity of 0 disables it.
+pub struct RackCache {
+    entries: Option<Mutex<Entries>>,
+    capacity: usize,
+    max_positions: usize,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    expirations: AtomicU64,
+    invalidations: AtomicU64,
+    oversized: AtomicU64,
+}
+
+struct Entries {
+    lru: LruCache<RackKey, Entry>,
+    /// Sum of `matches.len()` over `lru`
+    positions: usize,
+}
+
+impl Entries {
+    fn pop(&mut self, key: &RackKey) -> bool {
+        match self.lru.pop(key) {
+            Some(entry) => {
+                self.positions -= entry.matches.len();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl RackCache {
+    pub fn new(capacity: usize, max_positions: usize, ttl: Duration) -> Self {
+        RackCache {
+            entries: NonZeroUsize::new(capacity)
+                .map(|cap| Mutex::new(Entries { lru: LruCache::new(cap), positions: 0 })),
+            capacity,
+            max_positions,
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            expirations: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+            oversized: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, key: &RackKey) -> Option<Arc<Vec<u32>>> {
+        let entries = self.entries.as_ref()?;
+        let mut entries = entries.lock().unwrap();
+        let found = match entries.lru.get(key) {
+            Some(entry) if entry.inserted.elapsed() < self.ttl => Some(Arc::clone(&entry.matches)),
+            Some(_) => {
+                entries.pop(key);
+                self.expirations.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            None => None,
+        };
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    pub fn insert(&self, key: RackKey, matches: Arc<Vec<u32>>) {
+        let Some(entries) = self.entries.as_ref() else { return };
+        if matches.len() > self.max_positions {
+            self.oversized.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let mut entries = entries.lock().unwrap();
+        entries.pop(&key);
+        while entries.positions + matches.len() > self.max_positions {
+            let Some((_, evicted)) = entries.lru.pop_lru() else { break };
+            entries.positions -= evicted.matches.len();
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+
+        entries.positions += matches.len();
+        let entry = Entry { matches, inserted: Instant::now() };
+        if let Some((_, evicted)) = entries.lru.push(key, entry) {
+            entries.positions -= evicted.matches.len();
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Drop every entry for a language, e.g. after it was reloaded
+    pub fn invalidate(&self, lang: &str) {
+        let Some(entries) = self.entries.as_ref() else { return };
+        let mut entries = entries.lock().unwrap();
+        let stale: Vec<RackKey> = entries.lru.iter().filter(|(k, _)| k.lang == lang).map(|(k, _)| k.clone()).collect();
+        for key in &stale {
+            entries.pop(key);
+        }
+        self.invalidations.fetch_add(stale.len() as u64, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let (entries, positions) = self.entries.as_ref()
+            .map(|e| {
+                let e = e.lock().unwrap();
+                (e.lru.len(), e.positions)
+            })
+            .unwrap_or((0, 0));
+        CacheStats {
+            entries,
+            capacity: self.capacity,
+            positions,
+            max_positions: self.max_positions,
+            ttl_secs: self.ttl.as_secs(),
+            hits,
+            misses,
+            evictions: self.evictions.load(Ordering::Relaxed),
+            expirations: self.expirations.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            oversized: self.oversized.load(Ordering::Relaxed),
+            hit_rate: if hits + misses == 0 { 0.0 } else { hits as f64 / (hits + misses) as f64 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_racks_share_entries() {
+        let cache = RackCache::new(2, 100, Duration::from_secs(60));
+        let none = WordFilters::default();
+        cache.insert(RackKey::new("en", "h1", "tac_", None, None, &none), Arc::new(vec![1, 2]));
+        assert!(cache.get(&RackKey::new("en", "h1", "_CAT", None, None, &none)).is_some());
+        assert!(cache.get(&RackKey::new("en", "h1", "_CAT", Some(1), None, &none)).is_none());
+        let themed = WordFilters { required: Some("C".to_string()), ..WordFilters::default() };
+        assert!(cache.get(&RackKey::new("en", "h1", "_CAT", None, None, &themed)).is_none());
+        assert!(cache.get(&RackKey::new("en", "h2", "_CAT", None, None, &none)).is_none());
+
+        cache.insert(RackKey::new("en", "h1", "DOG", None, None, &none), Arc::new(vec![3]));
+        cache.insert(RackKey::new("fr", "h3", "CHAT", None, None, &none), Arc::new(vec![4]));
+        cache.invalidate("en");
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.hits, stats.misses, stats.evictions, stats.invalidations), (1, 1, 3, 1, 1));
+    }
+
+    #[test]
+    fn test_expired_entries_miss() {
+        let cache = RackCache::new(4, 100, Duration::ZERO);
+        let none = WordFilters::default();
+        cache.insert(RackKey::new("en", "h1", "CAT", None, None, &none), Arc::new(vec![1]));
+        assert!(cache.get(&RackKey::new("en", "h1", "CAT", None, None, &none)).is_none());
+        assert_eq!(cache.stats().expirations, 1);
+    }
+
+    #[test]
+    fn test_positions_budget() {
+        let cache = RackCache::new(10, 5, Duration::from_secs(60));
+        let none = WordFilters::default();
+        let key = |rack: &str| RackKey::new("en", "h1", rack, None, None, &none);
+        cache.insert(key("AB"), Arc::new(vec![1, 2]));
+        cache.insert(key("CD"), Arc::new(vec![3, 4]));
+        assert!(cache.get(&key("AB")).is_some());
+
+        // Three more positions don't fit beside four: the least recently used entry goes
+        cache.insert(key("EF"), Arc::new(vec![5, 6, 7]));
+        assert!(cache.get(&key("CD")).is_none());
+        assert!(cache.get(&key("AB")).is_some());
+        assert_eq!(cache.stats().positions, 5);
+
+        // Too big for the whole budget: not cached, nothing evicted for it
+        cache.insert(key("GH"), Arc::new(vec![0; 6]));
+        assert!(cache.get(&key("GH")).is_none());
+
+        // Replacing an entry releases its old positions first
+        cache.insert(key("AB"), Arc::new(vec![1]));
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.positions, stats.evictions, stats.oversized), (2, 4, 1, 1));
+
+        cache.invalidate("en");
+        assert_eq!(cache.stats().positions, 0);
+    }
+}
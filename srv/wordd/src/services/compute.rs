@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use log::{error, info};
+use tokio::sync::oneshot;
+
+use crate::services::budget::QueryError;
+
+/// Scheduling class of a lexicon query. Workers always take the highest
+/// non-empty class first, and `Low` work is shed first when the queue fills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    High,
+    Normal,
+    Low,
+}
+
+impl Priority {
+    /// Parse a client-supplied class name; unknown names fall back to `Normal`
+    pub fn parse(name: &str) -> Priority {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "high" => Priority::High,
+            "low" => Priority::Low,
+            _ => Priority::Normal,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct Queues {
+    classes: [VecDeque<Job>; 3],
+    len: usize,
+}
+
+struct Shared {
+    queues: Mutex<Queues>,
+    ready: Condvar,
+    capacity: usize,
+}
+
+/// Fixed-size thread pool for CPU-heavy lexicon queries, kept apart from the
+/// async workers so cheap lookups never wait behind a rack scan
+#[derive(Clone)]
+pub struct ComputePool {
+    shared: Arc<Shared>,
+}
+
+impl ComputePool {
+    pub fn new(threads: usize, capacity: usize) -> Self {
+        let shared = Arc::new(Shared {
+            queues: Mutex::new(Queues { classes: Default::default(), len: 0 }),
+            ready: Condvar::new(),
+            capacity,
+        });
+
+        for n in 0..threads.max(1) {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name(format!("wordd-compute-{}", n))
+                .spawn(move || worker(&shared))
+                .expect("failed to spawn compute thread");
+        }
+        info!("Compute pool: {} threads, queue capacity {}", threads.max(1), capacity);
+
+        ComputePool { shared }
+    }
+
+    /// Queue `f` and wait for its result. Fails with `Overloaded` when the queue is
+    /// full (half full, for `Low` work) rather than letting latency grow unbounded.
+    pub async fn run<T, F>(&self, priority: Priority, f: F) -> Result<T, QueryError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut queues = self.shared.queues.lock().unwrap();
+            let limit = match priority {
+                Priority::Low => self.shared.capacity / 2,
+                _ => self.shared.capacity,
+            };
+            if queues.len >= limit {
+                return Err(QueryError::Overloaded);
+            }
+            queues.classes[priority.index()].push_back(Box::new(move || {
+                // Skip work nobody is waiting for any more, e.g. after a client disconnect
+                if !tx.is_closed() {
+                    let _ = tx.send(f());
+                }
+            }));
+            queues.len += 1;
+        }
+        self.shared.ready.notify_one();
+
+        // A dropped sender means the job panicked
+        rx.await.map_err(|_| QueryError::Failed)
+    }
+}
+
+fn worker(shared: &Shared) {
+    loop {
+        let job = {
+            let mut queues = shared.queues.lock().unwrap();
+            loop {
+                if let Some(job) = queues.classes.iter_mut().find_map(|q| q.pop_front()) {
+                    queues.len -= 1;
+                    break job;
+                }
+                queues = shared.ready.wait(queues).unwrap();
+            }
+        };
+        if catch_unwind(AssertUnwindSafe(job)).is_err() {
+            error!("Compute job panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_runs_jobs_and_survives_panics() {
+        let pool = ComputePool::new(1, 4);
+        assert_eq!(pool.run(Priority::Normal, || 2 + 2).await.unwrap(), 4);
+        assert!(matches!(pool.run(Priority::High, || -> u8 { panic!("boom") }).await, Err(QueryError::Failed)));
+        assert_eq!(pool.run(Priority::Low, || "still up").await.unwrap(), "still up");
+    }
+
+    #[actix_web::test]
+    async fn test_full_queue_is_overloaded() {
+        let pool = ComputePool::new(1, 2);
+        let (started_tx, started_rx) = std::sync::mpsc::channel::<()>();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let blocker_pool = pool.clone();
+        let blocker = actix_web::rt::spawn(async move {
+            blocker_pool.run(Priority::Normal, move || {
+                started_tx.send(()).unwrap();
+                release_rx.recv().ok()
+            }).await
+        });
+        // Wait until the only worker is busy with the blocking job
+        while started_rx.try_recv().is_err() {
+            actix_web::rt::task::yield_now().await;
+        }
+
+        let queued_pool = pool.clone();
+        let queued = actix_web::rt::spawn(async move { queued_pool.run(Priority::Normal, || ()).await });
+        while pool.shared.queues.lock().unwrap().len < 1 {
+            actix_web::rt::task::yield_now().await;
+        }
+        // Low work is shed at half capacity, normal work only when full
+        assert!(matches!(pool.run(Priority::Low, || ()).await, Err(QueryError::Overloaded)));
+
+        release_tx.send(()).unwrap();
+        blocker.await.unwrap().unwrap();
+        queued.await.unwrap().unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_abandoned_jobs_are_skipped() {
+        let pool = ComputePool::new(1, 4);
+        let (started_tx, started_rx) = std::sync::mpsc::channel::<()>();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let blocker_pool = pool.clone();
+        let blocker = actix_web::rt::spawn(async move {
+            blocker_pool.run(Priority::Normal, move || {
+                started_tx.send(()).unwrap();
+                release_rx.recv().ok()
+            }).await
+        });
+        while started_rx.try_recv().is_err() {
+            actix_web::rt::task::yield_now().await;
+        }
+
+        // Queue a job behind the blocker, then stop waiting for it
+        let ran = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = Arc::clone(&ran);
+        let abandoned_pool = pool.clone();
+        let abandoned = actix_web::rt::spawn(async move {
+            abandoned_pool.run(Priority::Normal, move || flag.store(true, std::sync::atomic::Ordering::SeqCst)).await
+        });
+        while pool.shared.queues.lock().unwrap().len < 1 {
+            actix_web::rt::task::yield_now().await;
+        }
+        abandoned.abort();
+        assert!(abandoned.await.is_err());
+
+        release_tx.send(()).unwrap();
+        blocker.await.unwrap().unwrap();
+        // Jobs run in order on the single worker, so this one follows the abandoned one
+        pool.run(Priority::Normal, || ()).await.unwrap();
+        assert!(!ran.load(std::sync::atomic::Ordering::SeqCst));
+    }
+}
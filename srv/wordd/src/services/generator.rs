@@ -1,93 +1,292 @@
-use crate::models::Word;
+use crate::services::lexicon::Lexicon;
 use crate::utils::{contains_only_letters, count_vowels_consonants, compute_signature};
 use rand::seq::SliceRandom;
+use rand::Rng;
 use log::debug;
+use serde::Deserialize;
+use utoipa::IntoParams;
 
+use crate::services::alphagram::AlphagramIndex;
+use crate::services::budget::Budget;
+
+#[derive(Clone, Copy)]
 pub struct WordConstraints<'a> {
     pub letters: Option<&'a str>,
     pub min_vowels: Option<usize>,
     pub min_consonants: Option<usize>,
     pub vowels: &'a [char],
+    pub filters: &'a WordFilters,
+    /// The language's unicorns, for `filters.unicorn`
+    pub unicorns: &'a [char],
 }
 
-/// Find words in the dictionary that match the given constraints
-/// Returns a list of all matching words
-pub fn find_matching_words(
-    words: &[Word],
-    constraints: &WordConstraints
-) -> Vec<String> {
-    // Optimization: Pre-calculate max length if we have a letters constraint
-    let max_len = constraints.letters.map(|l| l.len()).unwrap_or(usize::MAX);
-    
-    // Optimization: Compute rack signature for fast filtering
-    let rack_sig = constraints.letters.map(compute_signature).unwrap_or(0);
+/// Restrictions on words beyond the rack and vowel counts, for themed rounds and
+/// Spelling-Bee-style puzzles. Part of the rack cache key, so `normalized` puts
+/// equivalent filters in one form.
+#[derive(Deserialize, IntoParams, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[into_params(parameter_in = Query)]
+pub struct WordFilters {
+    /// Letters every word must use at least once, e.g. the daily bonus letter
+    pub required: Option<String>,
+    /// Letters no word may use
+    pub excluded: Option<String>,
+    /// Fewest letters per word
+    pub min_len: Option<usize>,
+    /// Most letters per word
+    pub max_len: Option<usize>,
+    /// Words must start with this
+    pub starts_with: Option<String>,
+    /// Words must end with this
+    pub ends_with: Option<String>,
+    /// Words must use at least one of the language's unicorns
+    pub unicorn: Option<bool>,
+}
 
-    words.iter()
-        .filter(|word| {
-            // 1. Length constraint (cheap)
-            if word.len > max_len {
+impl WordFilters {
+    /// Uppercased, with letter sets sorted and deduplicated and no-op settings dropped
+    pub fn normalized(self) -> Self {
+        let letter_set = |letters: Option<String>| {
+            let mut set: Vec<char> = letters?.to_uppercase().chars().filter(|c| c.is_alphabetic()).collect();
+            set.sort_unstable();
+            set.dedup();
+            (!set.is_empty()).then(|| set.into_iter().collect())
+        };
+        let affix = |affix: Option<String>| affix.map(|a| a.to_uppercase()).filter(|a| !a.is_empty());
+        WordFilters {
+            required: letter_set(self.required),
+            excluded: letter_set(self.excluded),
+            min_len: self.min_len.filter(|&n| n > 0),
+            max_len: self.max_len,
+            starts_with: affix(self.starts_with),
+            ends_with: affix(self.ends_with),
+            unicorn: self.unicorn.filter(|&u| u),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == WordFilters::default()
+    }
+
+    fn matches(&self, word: &str, unicorns: &[char]) -> bool {
+        if self.min_len.is_some() || self.max_len.is_some() {
+            let len = word.chars().count();
+            if self.min_len.is_some_and(|min| len < min) || self.max_len.is_some_and(|max| len > max) {
                 return false;
             }
+        }
+        if self.starts_with.as_deref().is_some_and(|prefix| !word.starts_with(prefix))
+            || self.ends_with.as_deref().is_some_and(|suffix| !word.ends_with(suffix))
+        {
+            return false;
+        }
+        if self.required.as_deref().is_some_and(|required| !required.chars().all(|c| word.contains(c)))
+            || self.excluded.as_deref().is_some_and(|excluded| word.chars().any(|c| excluded.contains(c)))
+        {
+            return false;
+        }
+        self.unicorn.is_none() || word.chars().any(|c| unicorns.contains(&c))
+    }
+}
 
-            // 2. Bitmask Filter (Super cheap)
-            // If word has bits set that rack doesn't have, it's impossible match.
-            // Note: This assumes rack_sig represents available tiles. 
-            
-            let has_wildcard = constraints.letters.map(|l| l.contains('_')).unwrap_or(false);
-            if !has_wildcard && constraints.letters.is_some() && (word.signature & !rack_sig) != 0 {
-                 return false;
-            }
+/// One page of a budgeted scan over the word list
+pub struct ScanPage {
+    pub words: Vec<String>,
+    /// Where to resume the scan, if it stopped before the end of the word list
+    pub next_position: Option<usize>,
+}
 
-            // 3. Letters constraint (full check)
-            if let Some(available_letters) = constraints.letters {
-                if !contains_only_letters(&word.text, available_letters) {
-                    return false;
-                }
+impl ScanPage {
+    pub fn truncated(&self) -> bool {
+        self.next_position.is_some()
+    }
+}
+
+/// How often the scan checks the clock; `Instant::now` per word would dominate cheap rejections
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+fn word_matches(words: &Lexicon, pos: usize, constraints: &WordConstraints, max_len: usize, rack_sig: u32, has_wildcard: bool) -> bool {
+    let text = words.text(pos);
+
+    // 1. Length constraint (cheap; bytes only overcount letters, so count chars when they disagree)
+    if text.len() > max_len && text.chars().count() > max_len {
+        return false;
+    }
+
+    // 2. Bitmask Filter (Super cheap)
+    // If word has bits set that rack doesn't have, it's impossible match.
+    // Note: This assumes rack_sig represents available tiles. 
+    if !has_wildcard && constraints.letters.is_some() && (words.signature(pos) & !rack_sig) != 0 {
+         return false;
+    }
+
+    // 3. Letters constraint (full check)
+    if let Some(available_letters) = constraints.letters {
+        if !contains_only_letters(text, available_letters) {
+            return false;
+        }
+    }
+    
+    // 4. Rack structural constraints (vowels/consonants)
+    if constraints.min_vowels.is_some() || constraints.min_consonants.is_some() {
+        let (vowel_count, consonant_count) = count_vowels_consonants(text, constraints.vowels);
+        
+        if let Some(min_v) = constraints.min_vowels {
+            if vowel_count < min_v {
+                return false;
             }
-            
-            // 4. Rack structural constraints (vowels/consonants)
-            if constraints.min_vowels.is_some() || constraints.min_consonants.is_some() {
-                let (vowel_count, consonant_count) = count_vowels_consonants(&word.text, constraints.vowels);
-                
-                if let Some(min_v) = constraints.min_vowels {
-                    if vowel_count < min_v {
-                        return false;
-                    }
-                }
-                
-                if let Some(min_c) = constraints.min_consonants {
-                    if consonant_count < min_c {
-                        return false;
-                    }
-                }
+        }
+        
+        if let Some(min_c) = constraints.min_consonants {
+            if consonant_count < min_c {
+                return false;
             }
-            
-            true
-        })
-        .map(|w| w.text.clone())
+        }
+    }
+
+    // 5. Themed filters
+    constraints.filters.is_empty() || constraints.filters.matches(text, constraints.unicorns)
+}
+
+/// Scan the word list from `start` for words matching the constraints, stopping early
+/// once the budget's result limit or deadline is reached
+pub fn scan_linear(
+    words: &Lexicon,
+    constraints: &WordConstraints,
+    start: usize,
+    budget: &Budget,
+) -> ScanPage {
+    // Optimization: Pre-calculate max length if we have a letters constraint
+    let max_len = constraints.letters.map(|l| l.chars().count()).unwrap_or(usize::MAX);
+    
+    // Optimization: Compute rack signature for fast filtering
+    let rack_sig = constraints.letters.map(compute_signature).unwrap_or(0);
+    let has_wildcard = constraints.letters.map(|l| l.contains('_')).unwrap_or(false);
+
+    let mut found = Vec::new();
+    for offset in start..words.len() {
+        if found.len() >= budget.max_results
+            || ((offset - start) % DEADLINE_CHECK_INTERVAL == DEADLINE_CHECK_INTERVAL - 1 && budget.expired())
+        {
+            return ScanPage { words: found, next_position: Some(offset) };
+        }
+        if word_matches(words, offset, constraints, max_len, rack_sig, has_wildcard) {
+            found.push(words.text(offset).to_string());
+        }
+    }
+
+    ScanPage { words: found, next_position: None }
+}
+
+/// Lexicon positions, in word-list order, of every word matching a query that has a
+/// letters constraint, or None if the budget's deadline passed first. The alphagram
+/// index answers most racks; blank-heavy racks it can't enumerate cheaply are walked
+/// through the lexicon automaton instead, which can take a while on a long rack.
+pub fn rack_matches(
+    words: &Lexicon,
+    index: &AlphagramIndex,
+    rack: &str,
+    constraints: &WordConstraints,
+    budget: &Budget,
+) -> Option<Vec<u32>> {
+    // The budget may already be spent waiting in the compute queue
+    if budget.expired() {
+        return None;
+    }
+    let positions = match index.subanagrams(rack) {
+        Some(positions) => positions,
+        None => words.rack_positions_within(rack, budget)?,
+    };
+
+    // Both guarantee the letters fit, so only the remaining checks are repeated
+    let letter_free = WordConstraints { letters: None, ..*constraints };
+    let mut matches = Vec::new();
+    for (i, pos) in positions.into_iter().enumerate() {
+        if i % DEADLINE_CHECK_INTERVAL == DEADLINE_CHECK_INTERVAL - 1 && budget.expired() {
+            return None;
+        }
+        if word_matches(words, pos as usize, &letter_free, usize::MAX, 0, true) {
+            matches.push(pos);
+        }
+    }
+    Some(matches)
+}
+
+/// One page of words from a list of matching positions, resuming at lexicon position `start`
+pub fn page_positions(words: &Lexicon, matches: &[u32], start: usize, budget: &Budget) -> ScanPage {
+    let from = matches.partition_point(|&pos| (pos as usize) < start);
+    let mut found = Vec::new();
+    for &pos in &matches[from..] {
+        if found.len() >= budget.max_results {
+            return ScanPage { words: found, next_position: Some(pos as usize) };
+        }
+        found.push(words.text(pos as usize).to_string());
+    }
+
+    ScanPage { words: found, next_position: None }
+}
+
+/// Words matching the constraints from position `start`, in word-list order. Racks are
+/// answered from the alphagram index; only letter-free queries fall back to a scan.
+pub fn scan_matching_words(
+    words: &Lexicon,
+    index: &AlphagramIndex,
+    constraints: &WordConstraints,
+    start: usize,
+    budget: &Budget,
+) -> ScanPage {
+    match constraints.letters {
+        Some(rack) => match rack_matches(words, index, rack, constraints, budget) {
+            Some(matches) => page_positions(words, &matches, start, budget),
+            None => ScanPage { words: Vec::new(), next_position: Some(start) },
+        },
+        None => scan_linear(words, constraints, start, budget),
+    }
+}
+
+/// `count` random picks from a list of matching positions
+pub fn pick_random(words: &Lexicon, matches: &[u32], count: usize) -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..count)
+        .filter_map(|_| matches.choose(&mut rng))
+        .map(|&pos| words.text(pos as usize).to_string())
         .collect()
 }
 
-/// Select random words from the dictionary, respecting constraints
+/// Select random words from the dictionary, respecting constraints.
+/// Candidates are gathered until the budget's deadline; the flag is true if that
+/// cut the scan short, so the words were drawn from only part of the lexicon.
 pub fn select_random_words_with_constraints(
-    words: &[Word],
+    words: &Lexicon,
+    index: &AlphagramIndex,
     count: usize,
-    constraints: WordConstraints
-) -> Vec<String> {
+    constraints: WordConstraints,
+    budget: &Budget,
+) -> (Vec<String>, bool) {
     
     // Fast path: No constraints
-    if constraints.letters.is_none() && constraints.min_vowels.is_none() && constraints.min_consonants.is_none() {
+    if constraints.letters.is_none() && constraints.min_vowels.is_none() && constraints.min_consonants.is_none()
+        && constraints.filters.is_empty()
+    {
         let mut rng = rand::thread_rng();
-        return (0..count).map(|_| {
-             words.choose(&mut rng).map(|w| w.text.clone()).unwrap_or_else(|| "WORD".to_string())
+        let selected = (0..count).map(|_| {
+             if words.is_empty() {
+                 "WORD".to_string()
+             } else {
+                 words.text(rng.gen_range(0..words.len())).to_string()
+             }
         }).collect();
+        return (selected, false);
     }
 
-    let candidates = find_matching_words(words, &constraints);
+    // Every match is a candidate, so only the deadline limits the scan
+    let scan_budget = Budget { max_results: usize::MAX, ..*budget };
+    let page = scan_matching_words(words, index, &constraints, 0, &scan_budget);
+    let truncated = page.truncated();
+    let candidates = page.words;
     
     if candidates.is_empty() {
         debug!("No words found matching constraints");
-        return Vec::new();
+        return (Vec::new(), truncated);
     }
 
     let mut rng = rand::thread_rng();
@@ -99,5 +298,64 @@ pub fn select_random_words_with_constraints(
         }
     }
     
-    selected
+    (selected, truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters_narrow_rack_matches() {
+        let texts = ["AT", "CAT", "SCAT", "TAX", "TAXI"];
+        let lex = Lexicon::from_texts(&texts);
+        let index = AlphagramIndex::build(&lex);
+        let matching = |filters: WordFilters| {
+            let filters = filters.normalized();
+            let constraints = WordConstraints {
+                letters: Some("SCATXI"),
+                min_vowels: None,
+                min_consonants: None,
+                vowels: &['A', 'I'],
+                filters: &filters,
+                unicorns: &['X'],
+            };
+            rack_matches(&lex, &index, "SCATXI", &constraints, &Budget::unlimited())
+                .unwrap()
+                .into_iter()
+                .map(|pos| lex.text(pos as usize))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching(WordFilters::default()).len(), 5);
+        assert_eq!(matching(WordFilters { required: Some("tc".into()), ..Default::default() }), ["CAT", "SCAT"]);
+        assert_eq!(matching(WordFilters { excluded: Some("s x".into()), min_len: Some(3), ..Default::default() }), ["CAT"]);
+        assert_eq!(matching(WordFilters { starts_with: Some("ta".into()), max_len: Some(3), ..Default::default() }), ["TAX"]);
+        assert_eq!(matching(WordFilters { ends_with: Some("T".into()), unicorn: Some(true), ..Default::default() }), Vec::<&str>::new());
+        assert_eq!(matching(WordFilters { unicorn: Some(true), ..Default::default() }), ["TAX", "TAXI"]);
+        assert!(WordFilters { unicorn: Some(false), required: Some(" ".into()), ..Default::default() }.normalized().is_empty());
+    }
+
+    #[test]
+    fn test_rack_matches_stop_at_the_deadline() {
+        let lex = Lexicon::from_texts(&["AT", "CAT", "TA"]);
+        let index = AlphagramIndex::build(&lex);
+        let filters = WordFilters::default();
+        let constraints = WordConstraints {
+            letters: Some("___"),
+            min_vowels: None,
+            min_consonants: None,
+            vowels: &['A'],
+            filters: &filters,
+            unicorns: &[],
+        };
+        let spent = Budget { max_results: usize::MAX, deadline: std::time::Instant::now() };
+        assert_eq!(rack_matches(&lex, &index, "___", &constraints, &spent), None);
+        assert_eq!(lex.rack_positions_within("___", &spent), None);
+        assert_eq!(rack_matches(&lex, &index, "___", &constraints, &Budget::unlimited()).map(|m| m.len()), Some(3));
+
+        // A spent budget truncates a paged scan instead of returning a partial answer
+        let page = scan_matching_words(&lex, &index, &constraints, 0, &spent);
+        assert!(page.words.is_empty() && page.truncated());
+    }
 }
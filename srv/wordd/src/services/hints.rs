@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::services::lexicon::Lexicon;
+use crate::services::scoring::{best_play, LetterValues, RackTiles};
+
+/// Level that reveals the word itself
+pub const MAX_HINT_LEVEL: u8 = 4;
+
+/// Stands in for a hidden letter in a hint pattern (`_` already means a blank tile)
+const MASK: char = '?';
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct Hint {
+    pub language: String,
+    pub rack: String,
+    pub level: u8,
+    pub max_level: u8,
+    /// Level 1 on: letters in the best word (0 if nothing is playable)
+    pub length: usize,
+    /// Level 2 on
+    pub first_letter: Option<char>,
+    /// Level 3 on: first and last letters shown, the rest masked with `?`
+    pub pattern: Option<String>,
+    /// Level 4
+    pub word: Option<String>,
+    /// Playable words per length, at every level
+    pub counts_by_length: BTreeMap<usize, usize>,
+}
+
+fn pattern(word: &str) -> String {
+    let letters: Vec<char> = word.chars().collect();
+    letters
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| if i == 0 || i + 1 == letters.len() { c } else { MASK })
+        .collect()
+}
+
+/// Hints toward the rack's best word: highest letter score plus length bonus, then
+/// longest, then alphabetically first, so the same rack always gets the same answer
+pub fn hint(lang: &str, rack: &str, words: &Lexicon, matches: &[u32], values: &LetterValues, level: u8) -> Hint {
+    let tiles = RackTiles::new(rack);
+    let level = level.clamp(1, MAX_HINT_LEVEL);
+
+    let mut counts_by_length = BTreeMap::new();
+    let mut best: Option<(u32, usize, &str)> = None;
+    for &pos in matches {
+        let word = words.text(pos as usize);
+        let len = word.chars().filter(|c| c.is_alphabetic()).count();
+        *counts_by_length.entry(len).or_insert(0) += 1;
+
+        let score = best_play(&tiles, word, values);
+        // Equal lengths come alphabetically, so only a strictly better word replaces the best
+        if best.is_none_or(|(s, l, _)| (score, len) > (s, l)) {
+            best = Some((score, len, word));
+        }
+    }
+
+    let best = best.map(|(_, _, word)| word);
+    Hint {
+        language: lang.to_string(),
+        rack: rack.to_uppercase(),
+        level,
+        max_level: MAX_HINT_LEVEL,
+        length: best.map(|w| w.chars().count()).unwrap_or(0),
+        first_letter: best.filter(|_| level >= 2).and_then(|w| w.chars().next()),
+        pattern: best.filter(|_| level >= 3).map(pattern),
+        word: best.filter(|_| level >= 4).map(str::to_string),
+        counts_by_length,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::scoring::parse_values;
+
+    #[test]
+    fn test_hints_reveal_progressively() {
+        let texts = ["AT", "CAT", "SCAT", "TA"];
+        let lex = Lexicon::from_texts(&texts);
+        let matches = lex.rack_positions("SCAT");
+        let values = parse_values("A:1,C:3,S:1,T:1").unwrap();
+
+        let first = hint("en", "scat", &lex, &matches, &values, 1);
+        assert_eq!((first.length, first.first_letter, first.pattern.as_deref()), (4, None, None));
+        assert_eq!(first.counts_by_length, BTreeMap::from([(2, 2), (3, 1), (4, 1)]));
+
+        let third = hint("en", "scat", &lex, &matches, &values, 3);
+        assert_eq!((third.first_letter, third.pattern.as_deref(), third.word.as_deref()), (Some('S'), Some("S??T"), None));
+        assert_eq!(hint("en", "scat", &lex, &matches, &values, 9).word.as_deref(), Some("SCAT"));
+    }
+
+    #[test]
+    fn test_hint_edge_cases() {
+        let lex = Lexicon::from_texts(&["AT", "TA"]);
+        let values = parse_values("A:1,T:1").unwrap();
+
+        let none = hint("en", "qz", &lex, &[], &values, MAX_HINT_LEVEL);
+        assert_eq!((none.length, none.first_letter, none.word), (0, None, None));
+        assert!(none.counts_by_length.is_empty());
+
+        // Both words tie however the rack is made up, so the first alphabetically wins
+        for rack in ["ta", "__"] {
+            let found = hint("en", rack, &lex, &lex.rack_positions(rack), &values, MAX_HINT_LEVEL);
+            assert_eq!(found.word.as_deref(), Some("AT"), "rack {}", rack);
+        }
+    }
+}
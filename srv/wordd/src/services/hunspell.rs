@@ -0,0 +1,375 @@
+use std::collections::{BTreeSet, HashMap};
+use encoding_rs::Encoding;
+
+/// How flags are written in the .aff and .dic files (`FLAG` directive)
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FlagMode {
+    Char,
+    Long,
+    Num,
+}
+
+/// One element of an affix condition: `.`, a literal, or a `[...]` / `[^...]` class
+#[derive(Debug)]
+enum CondElem {
+    Any,
+    Class(Vec<char>, bool),
+}
+
+impl CondElem {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            CondElem::Any => true,
+            CondElem::Class(chars, negated) => chars.contains(&ch) != *negated,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AffixRule {
+    strip: Vec<char>,
+    add: Vec<char>,
+    condition: Vec<CondElem>,
+    cross_product: bool,
+}
+
+/// Parsed affix file: flag syntax, special flags, and PFX/SFX rules by flag
+#[derive(Debug)]
+pub struct AffixFile {
+    flag_mode: FlagMode,
+    flag_aliases: Vec<Vec<String>>,
+    prefixes: HashMap<String, Vec<AffixRule>>,
+    suffixes: HashMap<String, Vec<AffixRule>>,
+    need_affix: Option<String>,
+    forbidden: Option<String>,
+    only_in_compound: Option<String>,
+}
+
+/// Map a Hunspell `SET` value onto an encoding, defaulting to UTF-8
+fn encoding_for_set(set: &str) -> &'static Encoding {
+    let label = match set.to_ascii_lowercase().as_str() {
+        "microsoft-cp1251" => "windows-1251".to_string(),
+        other => other.to_string(),
+    };
+    Encoding::for_label(label.as_bytes()).unwrap_or(encoding_rs::UTF_8)
+}
+
+/// Find the `SET` directive in raw .aff bytes (the directive itself is always ASCII)
+pub fn detect_encoding(aff_bytes: &[u8]) -> &'static Encoding {
+    for line in aff_bytes.split(|&b| b == b'\n') {
+        let line = String::from_utf8_lossy(line);
+        let mut parts = line.split_whitespace();
+        if parts.next() == Some("SET") {
+            if let Some(set) = parts.next() {
+                return encoding_for_set(set);
+            }
+        }
+    }
+    encoding_rs::UTF_8
+}
+
+/// Decode raw file bytes with the given encoding (a BOM, if present, wins)
+pub fn decode(bytes: &[u8], encoding: &'static Encoding) -> String {
+    encoding.decode(bytes).0.into_owned()
+}
+
+fn parse_condition(cond: &str) -> Vec<CondElem> {
+    let mut elems = Vec::new();
+    let mut chars = cond.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '.' => elems.push(CondElem::Any),
+            '[' => {
+                let mut class = Vec::new();
+                let mut negated = false;
+                for c in chars.by_ref() {
+                    match c {
+                        ']' => break,
+                        '^' if class.is_empty() && !negated => negated = true,
+                        _ => class.push(c),
+                    }
+                }
+                elems.push(CondElem::Class(class, negated));
+            }
+            _ => elems.push(CondElem::Class(vec![ch], false)),
+        }
+    }
+    elems
+}
+
+/// Strip the `/flags` continuation class from an affix and treat `0` as empty
+fn parse_affix_text(text: &str) -> Vec<char> {
+    let text = text.split('/').next().unwrap_or("");
+    if text == "0" { Vec::new() } else { text.chars().collect() }
+}
+
+impl AffixFile {
+    pub fn parse(contents: &str) -> Self {
+        let mut aff = AffixFile {
+            flag_mode: FlagMode::Char,
+            flag_aliases: Vec::new(),
+            prefixes: HashMap::new(),
+            suffixes: HashMap::new(),
+            need_affix: None,
+            forbidden: None,
+            only_in_compound: None,
+        };
+        let mut cross_products: HashMap<(String, String), bool> = HashMap::new();
+        // The first `AF` line is the alias count; every later one is an alias, which
+        // under `FLAG num` may itself be a single number
+        let mut af_counted = false;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+
+            match parts.as_slice() {
+                ["FLAG", mode, ..] => {
+                    aff.flag_mode = match *mode {
+                        "long" => FlagMode::Long,
+                        "num" => FlagMode::Num,
+                        _ => FlagMode::Char,
+                    };
+                }
+                ["AF", count, ..] if !af_counted && count.parse::<usize>().is_ok() => af_counted = true,
+                ["AF", flags, ..] => {
+                    let parsed = aff.parse_flags(flags);
+                    aff.flag_aliases.push(parsed);
+                }
+                ["NEEDAFFIX", flag, ..] => aff.need_affix = Some(flag.to_string()),
+                ["FORBIDDENWORD", flag, ..] => aff.forbidden = Some(flag.to_string()),
+                ["ONLYINCOMPOUND", flag, ..] => aff.only_in_compound = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, cross, count]
+                    if count.parse::<usize>().is_ok() && (*cross == "Y" || *cross == "N") =>
+                {
+                    cross_products.insert((kind.to_string(), flag.to_string()), *cross == "Y");
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let condition = rest.first().copied().unwrap_or(".");
+                    let cross_product = cross_products
+                        .get(&(kind.to_string(), flag.to_string()))
+                        .copied()
+                        .unwrap_or(false);
+                    let rule = AffixRule {
+                        strip: parse_affix_text(strip),
+                        add: parse_affix_text(add),
+                        condition: parse_condition(condition),
+                        cross_product,
+                    };
+                    let rules = if *kind == "PFX" { &mut aff.prefixes } else { &mut aff.suffixes };
+                    rules.entry(flag.to_string()).or_default().push(rule);
+                }
+                _ => {}
+            }
+        }
+
+        aff
+    }
+
+    /// Split a .dic flag field, which is an `AF` alias number whenever the file defines aliases
+    fn split_flags(&self, flags: &str) -> Vec<String> {
+        if !self.flag_aliases.is_empty() {
+            if let Ok(idx) = flags.parse::<usize>() {
+                return self.flag_aliases.get(idx.wrapping_sub(1)).cloned().unwrap_or_default();
+            }
+        }
+        self.parse_flags(flags)
+    }
+
+    /// Split a flag string according to the `FLAG` mode
+    fn parse_flags(&self, flags: &str) -> Vec<String> {
+        match self.flag_mode {
+            FlagMode::Char => flags.chars().map(|c| c.to_string()).collect(),
+            FlagMode::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagMode::Num => flags.split(',').map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect(),
+        }
+    }
+
+    fn has_flag(flags: &[String], flag: &Option<String>) -> bool {
+        flag.as_ref().is_some_and(|f| flags.contains(f))
+    }
+
+    /// Expand one .dic entry into the root plus all its prefixed/suffixed forms
+    pub fn expand(&self, word: &str, flags: &str) -> Vec<String> {
+        let flags = self.split_flags(flags);
+        if Self::has_flag(&flags, &self.forbidden) || Self::has_flag(&flags, &self.only_in_compound) {
+            return Vec::new();
+        }
+
+        let root: Vec<char> = word.chars().collect();
+        let mut forms = Vec::new();
+        if !Self::has_flag(&flags, &self.need_affix) {
+            forms.push(word.to_string());
+        }
+
+        // Suffixed forms, remembering which ones may also take a prefix
+        let mut cross_suffixed: Vec<Vec<char>> = Vec::new();
+        for flag in &flags {
+            for rule in self.suffixes.get(flag).into_iter().flatten() {
+                if let Some(form) = apply_suffix(&root, rule) {
+                    if rule.cross_product {
+                        cross_suffixed.push(form.clone());
+                    }
+                    forms.push(form.into_iter().collect());
+                }
+            }
+        }
+
+        // Prefixed forms, combined with cross-product suffixes
+        for flag in &flags {
+            for rule in self.prefixes.get(flag).into_iter().flatten() {
+                if let Some(form) = apply_prefix(&root, rule) {
+                    forms.push(form.into_iter().collect());
+                }
+                if rule.cross_product {
+                    for suffixed in &cross_suffixed {
+                        if let Some(form) = apply_prefix(suffixed, rule) {
+                            forms.push(form.into_iter().collect());
+                        }
+                    }
+                }
+            }
+        }
+
+        forms
+    }
+}
+
+fn apply_suffix(word: &[char], rule: &AffixRule) -> Option<Vec<char>> {
+    if word.len() < rule.condition.len() || !word.ends_with(&rule.strip) {
+        return None;
+    }
+    let tail = &word[word.len() - rule.condition.len()..];
+    if !rule.condition.iter().zip(tail).all(|(cond, &ch)| cond.matches(ch)) {
+        return None;
+    }
+    let mut form = word[..word.len() - rule.strip.len()].to_vec();
+    form.extend_from_slice(&rule.add);
+    Some(form)
+}
+
+fn apply_prefix(word: &[char], rule: &AffixRule) -> Option<Vec<char>> {
+    if word.len() < rule.condition.len() || !word.starts_with(&rule.strip) {
+        return None;
+    }
+    if !rule.condition.iter().zip(word).all(|(cond, &ch)| cond.matches(ch)) {
+        return None;
+    }
+    let mut form = rule.add.clone();
+    form.extend_from_slice(&word[rule.strip.len()..]);
+    Some(form)
+}
+
+/// Keep only plain lowercase alphabetic words of at least two letters.
+/// Anything with a capital is treated as a proper noun or abbreviation.
+fn accept_word(word: &str) -> bool {
+    word.chars().count() >= 2
+        && word.chars().all(|c| c.is_alphabetic())
+        && !word.chars().any(|c| c.is_uppercase())
+}
+
+/// Expand every entry of a .dic file and collect the accepted words in sorted order
+pub fn expand_dictionary(dic: &str, aff: &AffixFile) -> BTreeSet<String> {
+    let mut words = BTreeSet::new();
+    let mut lines = dic.lines();
+
+    // The first line is the approximate entry count
+    if let Some(first) = lines.next() {
+        if first.trim().parse::<usize>().is_err() {
+            lines = dic.lines();
+        }
+    }
+
+    for line in lines {
+        // Morphological fields follow the entry after whitespace
+        let entry = line.split(['\t', ' ']).next().unwrap_or("").trim();
+        if entry.is_empty() || entry.starts_with('#') { continue; }
+        let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+
+        for form in aff.expand(word, flags) {
+            if accept_word(&form) {
+                words.insert(form.to_lowercase());
+            }
+        }
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\n\
+        NEEDAFFIX !\n\
+        PFX U Y 1\n\
+        PFX U 0 un .\n\
+        SFX S Y 2\n\
+        SFX S y ies [^aeiou]y\n\
+        SFX S 0 s [^y]\n\
+        SFX D N 1\n\
+        SFX D 0 ed [^e]\n";
+
+    #[test]
+    fn test_expand_prefix_suffix_cross_product() {
+        let aff = AffixFile::parse(AFF);
+        let mut forms = aff.expand("do", "US");
+        forms.sort();
+        assert_eq!(forms, vec!["do", "dos", "undo", "undos"]);
+
+        let mut forms = aff.expand("carry", "S");
+        forms.sort();
+        assert_eq!(forms, vec!["carries", "carry"]);
+    }
+
+    #[test]
+    fn test_expand_needaffix_and_non_cross_suffix() {
+        let aff = AffixFile::parse(AFF);
+        let mut forms = aff.expand("lock", "UD!");
+        forms.sort();
+        assert_eq!(forms, vec!["locked", "unlock"]);
+    }
+
+    #[test]
+    fn test_expand_dictionary_filters_and_sorts() {
+        let aff = AffixFile::parse(AFF);
+        let dic = "4\nzebra/S\nParis\nx\nco-op\napple/S\tpo:noun\n";
+        let words: Vec<String> = expand_dictionary(dic, &aff).into_iter().collect();
+        assert_eq!(words, vec!["apple", "apples", "zebra", "zebras"]);
+    }
+
+    #[test]
+    fn test_long_flags_and_latin1() {
+        let aff_bytes = b"SET ISO8859-1\nFLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let encoding = detect_encoding(aff_bytes);
+        let aff = AffixFile::parse(&decode(aff_bytes, encoding));
+        let dic = decode(b"1\ncaf\xe9/Aa\n", encoding);
+        let words: Vec<String> = expand_dictionary(&dic, &aff).into_iter().collect();
+        assert_eq!(words, vec!["café", "cafés"]);
+    }
+
+    #[test]
+    fn test_numeric_flag_aliases() {
+        // Alias 1 is the lone flag 7, which must not be mistaken for the AF count
+        let aff = AffixFile::parse("FLAG num
+AF 2
+AF 7
+AF 7,12
+SFX 7 Y 1
+SFX 7 0 s .
+PFX 12 Y 1
+PFX 12 0 re .
+");
+        let dic = "3
+cat/1
+do/2
+run/7
+";
+        let words: Vec<String> = expand_dictionary(dic, &aff).into_iter().collect();
+        assert_eq!(words, vec!["cat", "cats", "do", "dos", "redo", "redos", "run"]);
+    }
+}
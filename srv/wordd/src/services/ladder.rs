@@ -0,0 +1,229 @@
+use std::collections::{HashMap, VecDeque};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::Word;
+use crate::services::lexicon::Lexicon;
+
+/// Random start words tried before giving up on a puzzle
+const PUZZLE_ATTEMPTS: usize = 50;
+
+/// Words of one length and which of them differ by a single letter. Neighbour lists
+/// are packed back to back: word `i`'s are `edges[starts[i]..starts[i + 1]]`.
+struct LengthGraph {
+    /// Lexicon positions, ascending
+    positions: Vec<u32>,
+    starts: Vec<u32>,
+    /// Indices into `positions`
+    edges: Vec<u32>,
+}
+
+impl LengthGraph {
+    fn neighbours(&self, node: usize) -> &[u32] {
+        &self.edges[self.starts[node] as usize..self.starts[node + 1] as usize]
+    }
+
+    fn node(&self, pos: u32) -> Option<usize> {
+        self.positions.binary_search(&pos).ok()
+    }
+
+    /// Steps from `from` to every word, `u32::MAX` where unreachable, plus the
+    /// predecessor of each word on one shortest path
+    fn bfs(&self, from: usize, stop_at: Option<usize>) -> (Vec<u32>, Vec<u32>) {
+        let mut dist = vec![u32::MAX; self.positions.len()];
+        let mut prev = vec![u32::MAX; self.positions.len()];
+        let mut queue = VecDeque::from([from]);
+        dist[from] = 0;
+        while let Some(node) = queue.pop_front() {
+            if Some(node) == stop_at {
+                break;
+            }
+            for &next in self.neighbours(node) {
+                let next = next as usize;
+                if dist[next] == u32::MAX {
+                    dist[next] = dist[node] + 1;
+                    prev[next] = node as u32;
+                    queue.push_back(next);
+                }
+            }
+        }
+        (dist, prev)
+    }
+
+    fn path(&self, prev: &[u32], to: usize) -> Vec<u32> {
+        let mut path = vec![self.positions[to]];
+        let mut node = to;
+        while prev[node] != u32::MAX {
+            node = prev[node] as usize;
+            path.push(self.positions[node]);
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// One-letter-change neighbours of every word, grouped by word length
+pub struct LadderIndex {
+    by_len: HashMap<usize, LengthGraph>,
+}
+
+impl LadderIndex {
+    /// Build from the load pipeline's sorted word list, so positions match the lexicon's.
+    /// Words sharing a pattern with one letter masked out (`C?T` for CAT and COT) are neighbours.
+    pub fn build(words: &[Word]) -> Self {
+        let mut groups: HashMap<usize, Vec<u32>> = HashMap::new();
+        for (pos, word) in words.iter().enumerate() {
+            groups.entry(word.text.chars().count()).or_default().push(pos as u32);
+        }
+
+        let by_len = groups
+            .into_iter()
+            .map(|(len, positions)| {
+                let mut buckets: HashMap<String, Vec<u32>> = HashMap::new();
+                for (node, &pos) in positions.iter().enumerate() {
+                    let text = &words[pos as usize].text;
+                    for i in 0..len {
+                        let masked: String = text.chars().enumerate()
+                            .map(|(j, c)| if i == j { '?' } else { c })
+                            .collect();
+                        buckets.entry(masked).or_default().push(node as u32);
+                    }
+                }
+
+                // Two words differing in one letter share exactly one bucket, so no duplicates
+                let mut adjacency: Vec<Vec<u32>> = vec![Vec::new(); positions.len()];
+                for bucket in buckets.values().filter(|b| b.len() > 1) {
+                    for &a in bucket {
+                        adjacency[a as usize].extend(bucket.iter().filter(|&&b| b != a));
+                    }
+                }
+
+                let mut starts = Vec::with_capacity(positions.len() + 1);
+                let mut edges = Vec::new();
+                for mut list in adjacency {
+                    starts.push(edges.len() as u32);
+                    list.sort_unstable();
+                    edges.extend(list);
+                }
+                starts.push(edges.len() as u32);
+                (len, LengthGraph { positions, starts, edges })
+            })
+            .collect();
+
+        LadderIndex { by_len }
+    }
+
+    /// Approximate heap bytes held by the index
+    pub fn memory(&self) -> usize {
+        self.by_len.values()
+            .map(|g| (g.positions.capacity() + g.starts.capacity() + g.edges.capacity()) * 4)
+            .sum()
+    }
+
+    /// Lexicon positions along a shortest ladder from one word to another, both ends
+    /// included. None if either isn't a word, their lengths differ, or no ladder exists.
+    pub fn shortest_path(&self, words: &Lexicon, from: &str, to: &str) -> Option<Vec<u32>> {
+        let (from_pos, to_pos) = (words.position(from)?, words.position(to)?);
+        let graph = self.by_len.get(&from.chars().count())?;
+        let (start, goal) = (graph.node(from_pos as u32)?, graph.node(to_pos as u32)?);
+
+        let (dist, prev) = graph.bfs(start, Some(goal));
+        (dist[goal] != u32::MAX).then(|| graph.path(&prev, goal))
+    }
+
+    /// A random ladder of `len`-letter words whose shortest solution takes a number of
+    /// steps in the difficulty's range. Positions along that solution, or None if no
+    /// suitable pair turned up.
+    pub fn puzzle(&self, len: usize, difficulty: LadderDifficulty, rng: &mut impl Rng) -> Option<Vec<u32>> {
+        let graph = self.by_len.get(&len)?;
+        let (min, max) = difficulty.steps();
+        let candidates: Vec<usize> = (0..graph.positions.len())
+            .filter(|&node| !graph.neighbours(node).is_empty())
+            .collect();
+
+        for _ in 0..PUZZLE_ATTEMPTS {
+            let &start = candidates.choose(rng)?;
+            let (dist, prev) = graph.bfs(start, None);
+            let targets: Vec<usize> = (0..dist.len())
+                .filter(|&node| (min..=max).contains(&dist[node]))
+                .collect();
+            if let Some(&goal) = targets.choose(rng) {
+                return Some(graph.path(&prev, goal));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LadderDifficulty {
+    /// 2 to 3 steps
+    Easy,
+    /// 4 to 5 steps
+    #[default]
+    Medium,
+    /// 6 to 8 steps
+    Hard,
+}
+
+impl LadderDifficulty {
+    /// Inclusive range of steps in the shortest solution
+    fn steps(self) -> (u32, u32) {
+        match self {
+            LadderDifficulty::Easy => (2, 3),
+            LadderDifficulty::Medium => (4, 5),
+            LadderDifficulty::Hard => (6, 8),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct LadderPath {
+    pub language: String,
+    pub from: String,
+    pub to: String,
+    /// Both ends included; absent if the words aren't connected
+    pub path: Option<Vec<String>>,
+    /// Letter changes along the path
+    pub steps: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct LadderPuzzle {
+    pub language: String,
+    pub difficulty: LadderDifficulty,
+    pub start: String,
+    pub target: String,
+    /// Letter changes in the shortest solution
+    pub steps: usize,
+    /// One shortest solution, both ends included
+    pub solution: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::lexicon::test_words;
+
+    #[test]
+    fn test_shortest_path_and_puzzle() {
+        let words = test_words(&["CAT", "COT", "COG", "DOG", "DOT", "CATS", "EMU"]);
+        let lex = Lexicon::build(&words);
+        let ladders = LadderIndex::build(&words);
+        let texts_of = |path: Vec<u32>| path.into_iter().map(|p| lex.text(p as usize).to_string()).collect::<Vec<_>>();
+
+        let path = ladders.shortest_path(&lex, "CAT", "DOG").map(texts_of);
+        assert_eq!(path.map(|p| p.len()), Some(4));
+        assert_eq!(ladders.shortest_path(&lex, "CAT", "CAT").map(texts_of), Some(vec!["CAT".to_string()]));
+        assert!(ladders.shortest_path(&lex, "CAT", "EMU").is_none());
+        assert!(ladders.shortest_path(&lex, "CAT", "CATS").is_none());
+
+        let puzzle = ladders.puzzle(3, LadderDifficulty::Easy, &mut rand::thread_rng()).map(texts_of).unwrap();
+        assert!((3..=4).contains(&puzzle.len()));
+        assert!(ladders.puzzle(3, LadderDifficulty::Hard, &mut rand::thread_rng()).is_none());
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use log::info;
+use sha2::{Digest, Sha256};
+
+use crate::models::{LanguageData, Word};
+use crate::services::alphagram::AlphagramIndex;
+use crate::services::ladder::LadderIndex;
+use crate::services::lexicon::Lexicon;
+use crate::services::likelihood::Commonness;
+use crate::services::word_loader::LoadedWords;
+use crate::services::{distribution, letter_classifier, word_loader, wordle};
+
+/// Run the full load pipeline for one language: words, tile bag, and letter classes
+pub fn load_language(share_dir: &str, lang: &str, rack_size: usize, total_tiles: usize) -> LanguageData {
+    info!("Loading word list for language: {} (max_len: {})", lang, rack_size);
+    let loaded = word_loader::load_filtered_words(share_dir, lang, rack_size);
+    build_language(share_dir, lang, loaded, total_tiles)
+}
+
+/// Everything derived from a language's final word list. The optional frequency and
+/// offensive-word files are still read from `share_dir`.
+fn build_language(share_dir: &str, lang: &str, loaded: LoadedWords, total_tiles: usize) -> LanguageData {
+    let words = loaded.words;
+
+    // Calculate letter distribution directly from the filtered in-memory set
+    let freq = distribution::calculate_distribution_from_set(&words);
+    info!("Calculated letter distribution for {} ({} unique letters, from {} words)", lang, freq.len(), words.len());
+
+    // Compute tile bag
+    let tile_bag = distribution::compute_tile_bag(&freq, total_tiles);
+    info!("Computed tile bag for {} ({} total tiles)", lang, tile_bag.values().sum::<usize>());
+
+    // Classify letters
+    let (vowels, consonants, unicorns) = letter_classifier::classify_letters(&freq, lang);
+    info!("Classified letters for {}: {} vowels, {} consonants, {} unicorns",
+          lang, vowels.len(), consonants.len(), unicorns.len());
+
+    let hash = content_hash(&words, &tile_bag, &vowels, &consonants, &unicorns);
+    info!("Lexicon hash for {}: {}", lang, hash);
+
+    let ladders = LadderIndex::build(&words);
+    let words = Lexicon::build(&words);
+    let index = AlphagramIndex::build(&words);
+    let commonness = Commonness::load(share_dir, lang, &words, &freq);
+    let offensive = word_loader::load_offensive(share_dir, lang);
+    let wordle_targets = wordle::target_pool(&words, &commonness, &offensive);
+    info!("{} of {} {} words are eligible daily targets", wordle_targets.len(), words.len(), lang);
+
+    LanguageData {
+        hash,
+        words,
+        tile_bag,
+        vowels,
+        consonants,
+        unicorns,
+        inputs: loaded.inputs,
+        index,
+        commonness,
+        ladders,
+        wordle_targets,
+    }
+}
+
+/// A language built from a handful of words, with no share directory behind it
+#[cfg(test)]
+pub fn test_language(texts: &[&str]) -> LanguageData {
+    let texts: Vec<String> = texts.iter().map(|t| t.to_uppercase()).collect();
+    let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+    let words = crate::services::lexicon::test_words(&texts);
+    build_language("", "test", LoadedWords { words, inputs: Default::default() }, 100)
+}
+
+/// Hash everything clients can observe about a language. Letter sets and the bag are
+/// sorted first since their order comes from HashMap iteration and differs between runs.
+pub fn content_hash(
+    words: &[Word],
+    tile_bag: &HashMap<char, usize>,
+    vowels: &[char],
+    consonants: &[char],
+    unicorns: &[char],
+) -> String {
+    let mut hasher = Sha256::new();
+    for word in words {
+        hasher.update(word.text.as_bytes());
+        hasher.update(b"\n");
+    }
+
+    let mut bag: Vec<_> = tile_bag.iter().collect();
+    bag.sort();
+    for (letter, count) in bag {
+        hasher.update(format!("{}={};", letter, count).as_bytes());
+    }
+
+    for (label, set) in [("vowels", vowels), ("consonants", consonants), ("unicorns", unicorns)] {
+        let mut sorted = set.to_vec();
+        sorted.sort_unstable();
+        hasher.update(format!("\n{}:{}", label, sorted.iter().collect::<String>()).as_bytes());
+    }
+
+    // 16 bytes of SHA-256 is plenty to tell lexicons apart
+    hasher.finalize()[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash_ignores_map_order() {
+        let words = vec![Word { text: "CAT".to_string(), signature: 0, len: 3 }];
+        let mut bag = HashMap::new();
+        let mut reversed = HashMap::new();
+        for (i, c) in ['A', 'C', 'T', 'X', 'Y', 'Z'].into_iter().enumerate() {
+            bag.insert(c, i);
+        }
+        for (i, c) in ['A', 'C', 'T', 'X', 'Y', 'Z'].into_iter().enumerate().rev() {
+            reversed.insert(c, i);
+        }
+        assert_eq!(
+            content_hash(&words, &bag, &['A'], &['C', 'T'], &['X', 'Z']),
+            content_hash(&words, &reversed, &['A'], &['T', 'C'], &['Z', 'X']),
+        );
+        assert_ne!(
+            content_hash(&words, &bag, &['A'], &['C', 'T'], &['X', 'Z']),
+            content_hash(&words, &bag, &['A'], &['C', 'T'], &['X', 'Y']),
+        );
+
+        // Each build hashes its letters in a fresh HashMap order; letters tied on
+        // frequency must still be classified the same way every time
+        let texts = ["ANT", "BEE", "CAT", "DOG", "ELK", "FOX", "GNU", "HEN"];
+        let first = test_language(&texts).hash;
+        assert!((0..20).all(|_| test_language(&texts).hash == first));
+    }
+}
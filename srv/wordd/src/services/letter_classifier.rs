@@ -14,9 +14,10 @@ pub fn classify_letters(
         _ => vec!['A', 'E', 'I', 'O', 'U'], // Default to English
     };
 
-    // Identify unicorns (2 rarest letters)
+    // Identify unicorns (2 rarest letters), breaking ties by letter so the choice
+    // doesn't depend on HashMap order
     let mut sorted_letters: Vec<_> = freq.keys().cloned().collect();
-    sorted_letters.sort_by_key(|&c| freq[&c]);
+    sorted_letters.sort_by_key(|&c| (freq[&c], c));
     let unicorns: Vec<char> = sorted_letters.iter().take(2).cloned().collect();
 
     // Classify consonants (all letters not vowels)
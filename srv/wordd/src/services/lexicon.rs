@@ -0,0 +1,262 @@
+use fst::automaton::{Automaton, Str};
+use fst::{IntoStreamer, Map, MapBuilder, Streamer};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::models::Word;
+use crate::services::alphagram::alphagram;
+use crate::services::budget::Budget;
+
+/// A language's word list in compact form. A minimal automaton (FST) maps each word to
+/// its position in sorted order and answers membership, prefix, and rack queries; side
+/// tables indexed by position hold each word's text and letter signature, without a heap
+/// allocation per word.
+pub struct Lexicon {
+    fst: Map<Vec<u8>>,
+    /// Every word's text back to back; word `i` is `text[offsets[i]..offsets[i + 1]]`
+    text: String,
+    offsets: Vec<u32>,
+    signatures: Vec<u32>,
+}
+
+/// Bytes held by each part of a lexicon
+#[derive(Serialize, ToSchema, Debug, Clone, Copy)]
+pub struct LexiconMemory {
+    pub fst_bytes: usize,
+    pub text_bytes: usize,
+    pub side_table_bytes: usize,
+}
+
+impl Lexicon {
+    /// Build from the load pipeline's output, which is sorted and free of duplicates
+    pub fn build(words: &[Word]) -> Self {
+        let mut builder = MapBuilder::memory();
+        let mut text = String::new();
+        let mut offsets = Vec::with_capacity(words.len() + 1);
+        let mut signatures = Vec::with_capacity(words.len());
+        for (pos, word) in words.iter().enumerate() {
+            builder
+                .insert(&word.text, pos as u64)
+                .expect("word list must be sorted and unique");
+            offsets.push(text.len() as u32);
+            text.push_str(&word.text);
+            signatures.push(word.signature);
+        }
+        offsets.push(text.len() as u32);
+
+        let fst = builder.into_map();
+        text.shrink_to_fit();
+        Lexicon { fst, text, offsets, signatures }
+    }
+
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// The word at a position in sorted order
+    pub fn text(&self, pos: usize) -> &str {
+        &self.text[self.offsets[pos] as usize..self.offsets[pos + 1] as usize]
+    }
+
+    pub fn get(&self, pos: usize) -> Option<&str> {
+        (pos < self.len()).then(|| self.text(pos))
+    }
+
+    pub fn signature(&self, pos: usize) -> u32 {
+        self.signatures[pos]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.len()).map(move |pos| self.text(pos))
+    }
+
+    /// Exact membership; `word` must already be uppercased
+    pub fn contains(&self, word: &str) -> bool {
+        self.fst.contains_key(word)
+    }
+
+    /// Position of a word in sorted order; `word` must already be uppercased
+    pub fn position(&self, word: &str) -> Option<usize> {
+        self.fst.get(word).map(|pos| pos as usize)
+    }
+
+    /// Up to `limit` words starting with `prefix`, in sorted order, and whether more remain
+    pub fn with_prefix(&self, prefix: &str, limit: usize) -> (Vec<String>, bool) {
+        let mut stream = self.fst.search(Str::new(prefix).starts_with()).into_stream();
+        let mut words = Vec::new();
+        while let Some((key, _)) = stream.next() {
+            if words.len() >= limit {
+                return (words, true);
+            }
+            words.push(String::from_utf8_lossy(key).into_owned());
+        }
+        (words, false)
+    }
+
+    /// Sorted positions of every word that fits in `rack`, found by walking the automaton
+    /// with the rack's remaining letters. `_` is a blank matching any letter, as in
+    /// `contains_only_letters`.
+    pub fn rack_positions(&self, rack: &str) -> Vec<u32> {
+        self.rack_positions_within(rack, &Budget::unlimited()).unwrap_or_default()
+    }
+
+    /// As `rack_positions`, but None once the budget's deadline passes. The clock is
+    /// read at each match, so a walk that finds nothing for a while overruns a little.
+    pub fn rack_positions_within(&self, rack: &str, budget: &Budget) -> Option<Vec<u32>> {
+        let automaton = RackAutomaton::new(rack);
+        let mut stream = self.fst.search(&automaton).into_stream();
+        let mut positions = Vec::new();
+        while let Some((_, pos)) = stream.next() {
+            if budget.expired() {
+                return None;
+            }
+            positions.push(pos as u32);
+        }
+        Some(positions)
+    }
+
+    pub fn memory(&self) -> LexiconMemory {
+        LexiconMemory {
+            fst_bytes: self.fst.as_fst().as_bytes().len(),
+            text_bytes: self.text.capacity(),
+            side_table_bytes: (self.offsets.capacity() + self.signatures.capacity()) * std::mem::size_of::<u32>(),
+        }
+    }
+}
+
+/// Accepts words whose letters can be drawn from a rack. Transitions are on bytes, so
+/// the state buffers a multi-byte character until it is complete.
+struct RackAutomaton {
+    letters: Vec<char>,
+    start: RackState,
+}
+
+#[derive(Clone)]
+struct RackState {
+    /// Remaining count of each distinct rack letter, parallel to `RackAutomaton::letters`
+    remaining: Vec<u8>,
+    blanks: u8,
+    pending: [u8; 4],
+    pending_len: u8,
+}
+
+impl RackAutomaton {
+    fn new(rack: &str) -> Self {
+        let mut letters: Vec<char> = Vec::new();
+        let mut remaining: Vec<u8> = Vec::new();
+        for c in alphagram(rack).chars() {
+            if letters.last() == Some(&c) {
+                *remaining.last_mut().unwrap() = remaining.last().unwrap().saturating_add(1);
+            } else {
+                letters.push(c);
+                remaining.push(1);
+            }
+        }
+        let blanks = rack.chars().filter(|&c| c == '_').count().min(u8::MAX as usize) as u8;
+        RackAutomaton {
+            letters,
+            start: RackState { remaining, blanks, pending: [0; 4], pending_len: 0 },
+        }
+    }
+}
+
+fn utf8_width(first: u8) -> usize {
+    match first {
+        0xF0.. => 4,
+        0xE0.. => 3,
+        0xC0.. => 2,
+        _ => 1,
+    }
+}
+
+impl Automaton for RackAutomaton {
+    type State = Option<RackState>;
+
+    fn start(&self) -> Self::State {
+        Some(self.start.clone())
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        matches!(state, Some(s) if s.pending_len == 0)
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        let mut state = state.clone()?;
+        state.pending[state.pending_len as usize] = byte;
+        state.pending_len += 1;
+        let len = state.pending_len as usize;
+        if len < utf8_width(state.pending[0]) {
+            return Some(state);
+        }
+        state.pending_len = 0;
+
+        let c = std::str::from_utf8(&state.pending[..len]).ok().and_then(|s| s.chars().next())?;
+        // Non-letters in a word cost nothing, as in `contains_only_letters`
+        if !c.is_alphabetic() {
+            return Some(state);
+        }
+        match self.letters.iter().position(|&l| l == c) {
+            Some(i) if state.remaining[i] > 0 => state.remaining[i] -= 1,
+            _ if state.blanks > 0 => state.blanks -= 1,
+            _ => return None,
+        }
+        Some(state)
+    }
+}
+
+/// Words as the load pipeline would hand them over: sorted, unique, and signed
+#[cfg(test)]
+pub fn test_words(texts: &[&str]) -> Vec<Word> {
+    use crate::utils::compute_signature;
+
+    let mut texts: Vec<&str> = texts.to_vec();
+    texts.sort_unstable();
+    texts.dedup();
+    texts.into_iter()
+        .map(|t| Word { text: t.to_string(), signature: compute_signature(t), len: t.len() })
+        .collect()
+}
+
+#[cfg(test)]
+impl Lexicon {
+    /// A lexicon of a handful of words, in any order
+    pub fn from_texts(texts: &[&str]) -> Self {
+        Lexicon::build(&test_words(texts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::contains_only_letters;
+
+    #[test]
+    fn test_membership_and_prefixes() {
+        let lex = Lexicon::from_texts(&["CAT", "CATS", "CART", "DOG", "ÉTÉ"]);
+        assert!(lex.contains("CATS"));
+        assert!(!lex.contains("CA"));
+        assert_eq!(lex.text(4), "ÉTÉ");
+        assert_eq!(lex.with_prefix("CA", 10), (vec!["CART".to_string(), "CAT".to_string(), "CATS".to_string()], false));
+        assert!(lex.with_prefix("CA", 2).1);
+    }
+
+    #[test]
+    fn test_rack_positions_match_contains_only_letters() {
+        let lex = Lexicon::from_texts(&["AT", "TA", "CAT", "ACT", "TACT", "CART", "TRACT", "ÉTÉ", "TE", "EAT", "TEA", "ZZZ"]);
+        for rack in ["CAT", "TACT", "CA_", "__", "T_É", "RACT_", "___", "Z", "tac"] {
+            let expected: Vec<u32> = lex.iter().enumerate()
+                .filter(|(_, w)| contains_only_letters(w, rack))
+                .map(|(i, _)| i as u32)
+                .collect();
+            assert_eq!(lex.rack_positions(rack), expected, "rack {}", rack);
+        }
+    }
+}
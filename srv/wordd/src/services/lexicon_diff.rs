@@ -0,0 +1,128 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::models::LanguageData;
+
+/// What changed for one language between two loaded share directories
+pub struct LanguageDiff {
+    pub lang: String,
+    pub old_count: usize,
+    pub new_count: usize,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// word length -> (old count, new count), only lengths that changed
+    pub by_length: BTreeMap<usize, (usize, usize)>,
+    /// letter -> (old tiles, new tiles), only letters that changed
+    pub tile_bag: BTreeMap<char, (usize, usize)>,
+    pub unicorns: SetChange,
+    pub vowels: SetChange,
+    pub consonants: SetChange,
+}
+
+/// Letters added to and removed from a letter class
+#[derive(Default)]
+pub struct SetChange {
+    pub added: Vec<char>,
+    pub removed: Vec<char>,
+}
+
+impl SetChange {
+    fn between(old: &[char], new: &[char]) -> Self {
+        let old: BTreeSet<char> = old.iter().copied().collect();
+        let new: BTreeSet<char> = new.iter().copied().collect();
+        SetChange {
+            added: new.difference(&old).copied().collect(),
+            removed: old.difference(&new).copied().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl LanguageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.tile_bag.is_empty()
+            && self.unicorns.is_empty()
+            && self.vowels.is_empty()
+            && self.consonants.is_empty()
+    }
+}
+
+fn counts_by_length(data: &LanguageData) -> BTreeMap<usize, usize> {
+    let mut counts = BTreeMap::new();
+    for word in data.words.iter() {
+        *counts.entry(word.chars().count()).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn changed_entries<K: Ord + Copy>(old: &BTreeMap<K, usize>, new: &BTreeMap<K, usize>) -> BTreeMap<K, (usize, usize)> {
+    old.keys()
+        .chain(new.keys())
+        .map(|&k| (k, (old.get(&k).copied().unwrap_or(0), new.get(&k).copied().unwrap_or(0))))
+        .filter(|(_, (o, n))| o != n)
+        .collect()
+}
+
+fn sorted_bag(bag: &HashMap<char, usize>) -> BTreeMap<char, usize> {
+    bag.iter().map(|(&c, &n)| (c, n)).collect()
+}
+
+/// Compare two loads of the same language. Both word lists are sorted, so a merge walk finds
+/// additions and removals without building sets.
+pub fn diff_language(lang: &str, old: &LanguageData, new: &LanguageData) -> LanguageDiff {
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.words.len() || j < new.words.len() {
+        match (old.words.get(i), new.words.get(j)) {
+            (Some(o), Some(n)) if o == n => { i += 1; j += 1; }
+            (Some(o), Some(n)) if o < n => { removed.push(o.to_string()); i += 1; }
+            (Some(o), None) => { removed.push(o.to_string()); i += 1; }
+            (_, Some(n)) => { added.push(n.to_string()); j += 1; }
+            (None, None) => break,
+        }
+    }
+
+    LanguageDiff {
+        lang: lang.to_string(),
+        old_count: old.words.len(),
+        new_count: new.words.len(),
+        added,
+        removed,
+        by_length: changed_entries(&counts_by_length(old), &counts_by_length(new)),
+        tile_bag: changed_entries(&sorted_bag(&old.tile_bag), &sorted_bag(&new.tile_bag)),
+        unicorns: SetChange::between(&old.unicorns, &new.unicorns),
+        vowels: SetChange::between(&old.vowels, &new.vowels),
+        consonants: SetChange::between(&old.consonants, &new.consonants),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::language::test_language;
+
+    #[test]
+    fn test_merge_walk() {
+        let old = test_language(&["ANT", "BEE", "CAT", "DOG"]);
+        let new = test_language(&["BEE", "CAT", "COW", "EMU", "EWE"]);
+        let diff = diff_language("xx", &old, &new);
+        assert_eq!(diff.added, ["COW", "EMU", "EWE"]);
+        assert_eq!(diff.removed, ["ANT", "DOG"]);
+        assert_eq!((diff.old_count, diff.new_count), (4, 5));
+        assert_eq!(diff.by_length, BTreeMap::from([(3, (4, 5))]));
+        assert!(!diff.is_empty());
+
+        let diff = diff_language("xx", &old, &test_language(&["DOG", "CAT", "BEE", "ANT"]));
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.by_length, BTreeMap::new());
+
+        let empty = test_language(&[]);
+        assert_eq!(diff_language("xx", &empty, &old).added.len(), 4);
+        assert_eq!(diff_language("xx", &old, &empty).removed.len(), 4);
+    }
+}
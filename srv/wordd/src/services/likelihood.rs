@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use log::{info, warn};
+
+use crate::services::lexicon::Lexicon;
+use crate::services::scoring::RackTiles;
+use crate::services::word_file::read_word_file;
+
+/// How much each signal contributes to a duplicate-likelihood estimate
+const COMMONNESS_WEIGHT: f64 = 0.45;
+const LENGTH_WEIGHT: f64 = 0.35;
+const OBVIOUSNESS_WEIGHT: f64 = 0.2;
+
+/// Commonness of words a frequency list leaves out, relative to their letters' commonness.
+/// A list that doesn't name a word is evidence the word is obscure.
+const UNRANKED_DISCOUNT: f64 = 0.25;
+
+/// Commonness below which a word is too obscure to pick for players to guess.
+/// With a frequency list, every word it leaves out falls below this.
+const OBSCURE_BELOW: f64 = 0.3;
+
+pub fn frequency_path(base_dir: &str, lang: &str) -> String {
+    format!("{}/words/{}/frequency.txt", base_dir, lang)
+}
+
+/// How familiar each word in a lexicon is to players. An optional `frequency.txt`
+/// (most common word first; anything after the word on a line is ignored) gives usage
+/// ranks; without one, a word is as common as the letters it's made of.
+pub struct Commonness {
+    /// Usage rank per lexicon position, `u32::MAX` for words the list doesn't name
+    ranks: Option<Vec<u32>>,
+    ranked: usize,
+    /// Each letter's frequency relative to the most frequent letter
+    letter_share: HashMap<char, f64>,
+}
+
+impl Commonness {
+    pub fn load(base_dir: &str, lang: &str, words: &Lexicon, letter_freq: &HashMap<char, usize>) -> Self {
+        let most = letter_freq.values().copied().max().unwrap_or(1).max(1) as f64;
+        let letter_share = letter_freq.iter().map(|(&c, &n)| (c, n as f64 / most)).collect();
+
+        let path = frequency_path(base_dir, lang);
+        let ranks = match read_word_file(&path) {
+            Ok(file) => {
+                let mut ranks = vec![u32::MAX; words.len()];
+                let mut rank = 0;
+                for (_, line) in &file.lines {
+                    let word = line.split_whitespace().next().unwrap_or_default().to_uppercase();
+                    if let Some(pos) = words.position(&word) {
+                        if ranks[pos] == u32::MAX {
+                            ranks[pos] = rank;
+                            rank += 1;
+                        }
+                    }
+                }
+                info!("Ranked {} of {} {} words by frequency", rank, words.len(), lang);
+                Some((ranks, rank as usize))
+            }
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to load {}: {}", path, e);
+                }
+                None
+            }
+        };
+
+        let (ranks, ranked) = match ranks {
+            Some((ranks, ranked)) => (Some(ranks), ranked),
+            None => (None, 0),
+        };
+        Commonness { ranks, ranked, letter_share }
+    }
+
+    /// Too unfamiliar to make players guess
+    pub fn is_obscure(&self, words: &Lexicon, word: &str) -> bool {
+        self.score(words, word) < OBSCURE_BELOW
+    }
+
+    /// 0 (obscure) to 1 (everyday)
+    fn score(&self, words: &Lexicon, word: &str) -> f64 {
+        let letters: Vec<f64> = word.chars()
+            .filter(|c| c.is_alphabetic())
+            .map(|c| self.letter_share.get(&c).copied().unwrap_or(0.0))
+            .collect();
+        let letter_score = if letters.is_empty() { 0.0 } else { letters.iter().sum::<f64>() / letters.len() as f64 };
+
+        let Some(ranks) = &self.ranks else { return letter_score };
+        match words.position(word).map(|pos| ranks[pos]) {
+            Some(rank) if rank != u32::MAX => 1.0 - ((rank + 1) as f64).ln() / ((self.ranked + 1) as f64).ln(),
+            _ => UNRANKED_DISCOUNT * letter_score,
+        }
+    }
+}
+
+/// Estimated chance (0 to 1) that another player also finds each of `found` on `rack`,
+/// from how common the word is, how short it is, and how plainly it shows on the rack as
+/// dealt: words spelled by adjacent tiles stand out, and every blank it needs hides it further.
+pub fn duplicate_likelihoods(commonness: &Commonness, words: &Lexicon, rack: &str, found: &[String]) -> Vec<f32> {
+    let tiles = RackTiles::new(rack);
+    let dealt: String = rack.to_uppercase().chars().filter(|&c| c != '_').collect();
+
+    found.iter().map(|word| {
+        let len = word.chars().filter(|c| c.is_alphabetic()).count();
+        let length = if tiles.len() <= 2 {
+            1.0
+        } else {
+            1.0 - (len.saturating_sub(2) as f64 / (tiles.len() - 2) as f64).min(1.0)
+        };
+
+        let blanks_used = tiles.assign(word).map(|play| play.blanks.len()).unwrap_or(0);
+        let obviousness = if dealt.contains(word.as_str()) { 1.0 } else { 0.5 } * 0.5f64.powi(blanks_used as i32);
+
+        let estimate = COMMONNESS_WEIGHT * commonness.score(words, word)
+            + LENGTH_WEIGHT * length
+            + OBVIOUSNESS_WEIGHT * obviousness;
+        ((estimate.clamp(0.0, 1.0) * 100.0).round() / 100.0) as f32
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_plain_words_are_likelier_duplicates() {
+        let texts = ["AT", "CAT", "TACTS"];
+        let lex = Lexicon::from_texts(&texts);
+        let freq = HashMap::from([('A', 10), ('T', 8), ('C', 3), ('S', 5)]);
+        let commonness = Commonness::load("/nonexistent", "en", &lex, &freq);
+
+        let found = ["AT", "CAT", "TACTS"].map(String::from);
+        let [at, cat, tacts] = duplicate_likelihoods(&commonness, &lex, "CATS_", &found)[..] else { unreachable!() };
+        assert!(at > cat && cat > tacts, "{} {} {}", at, cat, tacts);
+        assert!((0.0..=1.0).contains(&tacts));
+    }
+}
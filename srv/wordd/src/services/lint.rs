@@ -0,0 +1,259 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::io;
+
+use crate::models::Word;
+use crate::services::word_file::{read_word_file, WordFile};
+use crate::services::word_loader::{
+    deletions_path, insertions_path, lexicon_path, rare_letters, MIN_LETTER_FREQUENCY,
+};
+use crate::utils::compute_signature;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Finding {
+    pub severity: Severity,
+    pub file: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", self.file, line, self.severity, self.message),
+            None => write!(f, "{}: {}: {}", self.file, self.severity, self.message),
+        }
+    }
+}
+
+/// Dropped words listed in the frequency-filter finding; the rest are only counted
+const DROPPED_SAMPLE: usize = 20;
+
+/// Coarse Unicode script of a letter, enough to catch words mixing alphabets.
+/// Modifier letters (the `ᵉ` in `2ᵉ`) count as their base script, and letterlike
+/// symbols such as `ℓ` belong to no script, so neither makes a word mixed.
+fn script_of(ch: char) -> Option<&'static str> {
+    let script = match ch as u32 {
+        0x1D26..=0x1D2A | 0x1D5D..=0x1D61 | 0x1D66..=0x1D6A | 0x1DBF => "Greek",
+        0x1D2B | 0x1D78 => "Cyrillic",
+        0x0041..=0x024F | 0x02B0..=0x02FF | 0x1D00..=0x1DBE | 0x1E00..=0x1EFF
+        | 0x2C60..=0x2C7F | 0xA720..=0xA7FF | 0xAB30..=0xAB6F | 0xFF21..=0xFF5A => "Latin",
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => "Greek",
+        0x0400..=0x052F => "Cyrillic",
+        0x0590..=0x05FF => "Hebrew",
+        0x0600..=0x06FF => "Arabic",
+        0x2100..=0x214F => return None,
+        _ => "Other",
+    };
+    Some(script)
+}
+
+fn is_mixed_script(word: &str) -> bool {
+    let scripts: HashSet<&str> = word.chars().filter(|c| c.is_alphabetic()).filter_map(script_of).collect();
+    scripts.len() > 1
+}
+
+/// The first `n` words, then how many more there are
+fn sample(words: &BTreeSet<&str>, n: usize) -> String {
+    let shown: Vec<&str> = words.iter().take(n).copied().collect();
+    match words.len().saturating_sub(n) {
+        0 => shown.join(", "),
+        more => format!("{}, ... and {} more", shown.join(", "), more),
+    }
+}
+
+/// Findings for a single language directory
+pub struct LangReport {
+    pub lang: String,
+    pub findings: Vec<Finding>,
+}
+
+impl LangReport {
+    fn push(&mut self, severity: Severity, file: &str, line: Option<usize>, message: String) {
+        self.findings.push(Finding { severity, file: file.to_string(), line, message });
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+}
+
+/// Read an optional curation file; returns None when it simply doesn't exist
+fn read_optional(path: &str, report: &mut LangReport) -> Option<WordFile> {
+    match read_word_file(path) {
+        Ok(file) => Some(file),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => {
+            report.push(Severity::Error, path, None, format!("cannot read file: {}", e));
+            None
+        }
+    }
+}
+
+/// Per-line checks shared by every word file: decoding, characters, scripts, duplicates
+fn check_lines(file: &WordFile, report: &mut LangReport) {
+    for issue in &file.issues {
+        report.push(Severity::Error, &file.path, Some(issue.line), issue.message.clone());
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut upper_seen: HashMap<String, (&str, usize)> = HashMap::new();
+
+    for (line, word) in &file.lines {
+        if !word.chars().all(|c| c.is_alphabetic()) {
+            report.push(Severity::Error, &file.path, Some(*line),
+                        format!("non-alphabetic characters in '{}'", word));
+        } else if is_mixed_script(word) {
+            report.push(Severity::Error, &file.path, Some(*line),
+                        format!("mixed scripts in '{}'", word));
+        }
+
+        if let Some(first) = seen.get(word.as_str()) {
+            report.push(Severity::Warning, &file.path, Some(*line),
+                        format!("duplicate '{}' (first on line {})", word, first));
+            continue;
+        }
+        seen.insert(word, *line);
+
+        let upper = word.to_uppercase();
+        match upper_seen.get(&upper) {
+            Some((other, other_line)) => report.push(Severity::Warning, &file.path, Some(*line),
+                format!("'{}' collides with '{}' (line {}) as '{}'", word, other, other_line, upper)),
+            None => { upper_seen.insert(upper, (word, *line)); }
+        }
+    }
+}
+
+/// Lint one language: per-file checks, then cross-file checks that mirror `load_filtered_words`
+pub fn lint_language(share_dir: &str, lang: &str, max_len: usize) -> LangReport {
+    let mut report = LangReport { lang: lang.to_string(), findings: Vec::new() };
+
+    let lexicon_file = lexicon_path(share_dir, lang);
+    let lexicon = match read_word_file(&lexicon_file) {
+        Ok(file) => file,
+        Err(e) => {
+            report.push(Severity::Error, &lexicon_file, None, format!("cannot read lexicon: {}", e));
+            return report;
+        }
+    };
+    let insertions = read_optional(&insertions_path(share_dir, lang), &mut report);
+    let deletions = read_optional(&deletions_path(share_dir, lang), &mut report);
+
+    for file in [Some(&lexicon), insertions.as_ref(), deletions.as_ref()].into_iter().flatten() {
+        check_lines(file, &mut report);
+    }
+
+    // Words and letters the frequency filter would drop
+    let all_words: HashSet<String> = lexicon.lines.iter().map(|(_, w)| w.to_uppercase()).collect();
+    let base: Vec<Word> = all_words.iter()
+        .filter(|w| w.chars().count() <= max_len)
+        .map(|text| Word { signature: compute_signature(text), len: text.len(), text: text.clone() })
+        .collect();
+    let rare = rare_letters(&base, MIN_LETTER_FREQUENCY);
+    let dropped: BTreeSet<&str> = base.iter()
+        .filter(|w| w.text.chars().any(|c| rare.contains(&c)))
+        .map(|w| w.text.as_str())
+        .collect();
+    if !rare.is_empty() {
+        let mut letters: Vec<char> = rare.iter().copied().collect();
+        letters.sort_unstable();
+        let letters: String = letters.into_iter().collect();
+        report.push(Severity::Info, &lexicon.path, None, format!(
+            "frequency filter drops letters [{}] and {} words: {}", letters, dropped.len(), sample(&dropped, DROPPED_SAMPLE)));
+    }
+
+    let kept: HashSet<&str> = base.iter()
+        .map(|w| w.text.as_str())
+        .filter(|w| !dropped.contains(w))
+        .collect();
+
+    if let Some(insertions) = &insertions {
+        for (line, word) in &insertions.lines {
+            if kept.contains(word.to_uppercase().as_str()) {
+                report.push(Severity::Warning, &insertions.path, Some(*line),
+                            format!("'{}' is already in the lexicon", word));
+            }
+        }
+    }
+
+    if let Some(deletions) = &deletions {
+        let inserted: HashSet<String> = insertions.iter()
+            .flat_map(|f| f.lines.iter().map(|(_, w)| w.to_uppercase()))
+            .collect();
+        for (line, word) in &deletions.lines {
+            let upper = word.to_uppercase();
+            if !all_words.contains(&upper) && !inserted.contains(&upper) {
+                report.push(Severity::Warning, &deletions.path, Some(*line),
+                            format!("'{}' matches nothing in the lexicon or insertions", word));
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_script() {
+        assert!(!is_mixed_script("ÉCOLE"));
+        // Abbreviations from the French lexicon
+        assert!(!is_mixed_script("Mᵉ"));
+        assert!(!is_mixed_script("Mᵍʳ"));
+        assert!(!is_mixed_script("mℓ"));
+        assert!(is_mixed_script("\u{3BC}M")); // Greek mu, not the micro sign
+        assert!(is_mixed_script("PАRIS")); // Cyrillic А
+        assert_eq!(script_of('ᴦ'), Some("Greek"));
+    }
+
+    #[test]
+    fn test_dropped_sample() {
+        let words: BTreeSet<&str> = ["A", "B", "C"].into_iter().collect();
+        assert_eq!(sample(&words, 5), "A, B, C");
+        assert_eq!(sample(&words, 2), "A, B, ... and 1 more");
+        assert_eq!(sample(&BTreeSet::new(), 2), "");
+    }
+
+    #[test]
+    fn test_lint_language() {
+        let share = std::env::temp_dir().join(format!("wordd-lint-{}", std::process::id()));
+        let dir = share.join("words").join("xx");
+        std::fs::create_dir_all(&dir).unwrap();
+        // 125 common words, plus one word per letter À..Ö so each of those is rare
+        let letters = ['A', 'B', 'C', 'D', 'E'];
+        let mut lexicon: Vec<String> = (0..125)
+            .map(|i| [letters[i / 25], letters[i / 5 % 5], letters[i % 5]].iter().collect())
+            .collect();
+        lexicon.extend(('À'..='Ö').map(|c| format!("A{}", c)));
+        lexicon.extend(["Mᵉ".to_string(), "PАRIS".to_string()]);
+        std::fs::write(dir.join("lexicon.txt"), lexicon.join("\n")).unwrap();
+
+        let report = lint_language(share.to_str().unwrap(), "xx", 7);
+        std::fs::remove_dir_all(&share).unwrap();
+        let messages: Vec<&str> = report.findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(report.count(Severity::Error), 1);
+        assert!(messages.contains(&"mixed scripts in 'PАRIS'"));
+        let dropped = messages.iter().find(|m| m.starts_with("frequency filter")).unwrap();
+        assert!(dropped.contains(" and 25 words: AÀ, AÁ,"), "{}", dropped);
+        assert!(dropped.ends_with(", ... and 5 more"), "{}", dropped);
+    }
+}
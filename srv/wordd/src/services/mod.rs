@@ -2,3 +2,22 @@ pub mod word_loader;
 pub mod distribution;
 pub mod letter_classifier;
 pub mod generator;
+pub mod rejections;
+pub mod hunspell;
+pub mod word_file;
+pub mod lint;
+pub mod language;
+pub mod lexicon_diff;
+pub mod solver;
+pub mod budget;
+pub mod compute;
+pub mod alphagram;
+pub mod cache;
+pub mod lexicon;
+pub mod scoring;
+pub mod rack_analysis;
+pub mod likelihood;
+pub mod round_analysis;
+pub mod hints;
+pub mod ladder;
+pub mod wordle;
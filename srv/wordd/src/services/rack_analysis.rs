@@ -0,0 +1,222 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::services::lexicon::Lexicon;
+use crate::services::scoring::{best_play, length_bonus, min_bonus_len, LetterValues, RackTiles};
+
+/// Racks with at least this many playable words (and a bonus-length word) rate easy
+const EASY_MIN_WORDS: usize = 40;
+/// Racks with fewer playable words than this rate hard
+const HARD_MAX_WORDS: usize = 10;
+
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Unplayable,
+    Hard,
+    Medium,
+    Easy,
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct RackAnalysis {
+    pub language: String,
+    pub rack: String,
+    pub playable_words: usize,
+    /// In letters; 0 if nothing is playable
+    pub longest_word_len: usize,
+    /// True if some word uses every tile, blanks included
+    pub full_rack_word: bool,
+    /// Highest score of any playable word, length bonus included
+    pub best_score: u32,
+    pub best_word: Option<String>,
+    pub vowels: usize,
+    pub consonants: usize,
+    pub blanks: usize,
+    /// True if the rack meets the minimum vowel and consonant counts
+    pub balanced: bool,
+    pub difficulty: Difficulty,
+}
+
+/// A word played from a rack, with what its blanks stand for
+#[derive(Serialize, ToSchema, Debug)]
+pub struct RackPlay {
+    pub word: String,
+    /// Letters played with blanks, in word order
+    pub blanks: Vec<char>,
+}
+
+/// A word that uses every tile but one
+#[derive(Serialize, ToSchema, Debug)]
+pub struct NearMiss {
+    pub word: String,
+    pub blanks: Vec<char>,
+    /// The unused tile; `_` for a blank
+    pub leftover: char,
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct FullRackWords {
+    pub language: String,
+    pub rack: String,
+    /// Tiles in the rack, blanks included
+    pub tiles: usize,
+    /// Words using every tile, alphabetically
+    pub anagrams: Vec<RackPlay>,
+    /// Length bonus a full-rack word earns
+    pub anagram_bonus: u32,
+    /// Words using every tile but one, alphabetically
+    pub near_misses: Vec<NearMiss>,
+    /// Length bonus a near miss earns
+    pub near_miss_bonus: u32,
+}
+
+/// Minimum vowels and consonants a dealt rack should have, as the backend deals them
+pub struct Balance {
+    pub min_vowels: usize,
+    pub min_consonants: usize,
+}
+
+/// Words that use all of the rack, or all but one tile, from the positions of every
+/// word it can form
+pub fn full_rack_words(lang: &str, rack: &str, words: &Lexicon, matches: &[u32]) -> FullRackWords {
+    let tiles = RackTiles::new(rack);
+    let mut anagrams = Vec::new();
+    let mut near_misses = Vec::new();
+    let min_len = tiles.len().saturating_sub(1);
+    for &pos in matches {
+        let word = words.text(pos as usize);
+        if word.chars().filter(|c| c.is_alphabetic()).count() < min_len {
+            continue;
+        }
+        let Some(play) = tiles.assign(word) else { continue };
+        match play.leftover.as_slice() {
+            [] => anagrams.push(RackPlay { word: word.to_string(), blanks: play.blanks }),
+            [leftover] => near_misses.push(NearMiss { word: word.to_string(), blanks: play.blanks, leftover: *leftover }),
+            _ => {}
+        }
+    }
+
+    FullRackWords {
+        language: lang.to_string(),
+        rack: rack.to_uppercase(),
+        tiles: tiles.len(),
+        anagrams,
+        anagram_bonus: length_bonus(tiles.len(), tiles.len()),
+        near_misses,
+        near_miss_bonus: length_bonus(min_len, tiles.len()),
+    }
+}
+
+/// Summarise a rack from the positions of every word it can form
+pub fn analyze(
+    lang: &str,
+    rack: &str,
+    words: &Lexicon,
+    matches: &[u32],
+    values: &LetterValues,
+    vowel_set: &[char],
+    balance: &Balance,
+) -> RackAnalysis {
+    let tiles = RackTiles::new(rack);
+
+    let mut longest = 0;
+    let mut best: Option<(u32, &str)> = None;
+    for &pos in matches {
+        let word = words.text(pos as usize);
+        longest = longest.max(word.chars().filter(|c| c.is_alphabetic()).count());
+        let score = best_play(&tiles, word, values);
+        // Ties go to the longer word, then the alphabetically first
+        let better = match best {
+            None => true,
+            Some((s, w)) => score > s || (score == s && word.chars().count() > w.chars().count()),
+        };
+        if better {
+            best = Some((score, word));
+        }
+    }
+
+    let vowels: usize = tiles.letters().filter(|(c, _)| vowel_set.contains(c)).map(|(_, n)| n).sum();
+    let consonants: usize = tiles.letters().filter(|(c, _)| !vowel_set.contains(c)).map(|(_, n)| n).sum();
+    let full_rack_word = longest > 0 && longest == tiles.len();
+    let bonus_len = min_bonus_len(tiles.len());
+
+    let difficulty = if matches.is_empty() {
+        Difficulty::Unplayable
+    } else if full_rack_word || (longest >= bonus_len && matches.len() >= EASY_MIN_WORDS) {
+        Difficulty::Easy
+    } else if longest < bonus_len || matches.len() < HARD_MAX_WORDS {
+        Difficulty::Hard
+    } else {
+        Difficulty::Medium
+    };
+
+    RackAnalysis {
+        language: lang.to_string(),
+        rack: rack.to_uppercase(),
+        playable_words: matches.len(),
+        longest_word_len: longest,
+        full_rack_word,
+        best_score: best.map(|(s, _)| s).unwrap_or(0),
+        best_word: best.map(|(_, w)| w.to_string()),
+        vowels,
+        consonants,
+        blanks: tiles.blanks,
+        balanced: vowels >= balance.min_vowels && consonants >= balance.min_consonants,
+        difficulty,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_rack_words() {
+        let texts = ["ACT", "AT", "CAT", "CATS", "SCAT", "TACT"];
+        let lex = Lexicon::from_texts(&texts);
+        let all: Vec<u32> = lex.rack_positions("CAT_");
+
+        let found = full_rack_words("en", "cat_", &lex, &all);
+        let anagrams: Vec<(&str, &[char])> = found.anagrams.iter().map(|p| (p.word.as_str(), p.blanks.as_slice())).collect();
+        assert_eq!(anagrams, vec![("CATS", &['S'][..]), ("SCAT", &['S'][..]), ("TACT", &['T'][..])]);
+        let near: Vec<(&str, char)> = found.near_misses.iter().map(|n| (n.word.as_str(), n.leftover)).collect();
+        assert_eq!(near, vec![("ACT", '_'), ("CAT", '_')]);
+    }
+
+    #[test]
+    fn test_analyze_counts_the_length_bonus() {
+        let texts = ["CRATES", "ZAC"];
+        let lex = Lexicon::from_texts(&texts);
+        let all = lex.rack_positions("ZACRETS");
+        let values = crate::services::scoring::parse_values("Z:10, A:1, C:5, R:1, E:1, T:2, S:1").unwrap();
+        let balance = Balance { min_vowels: 1, min_consonants: 1 };
+
+        // ZAC outscores CRATES on letters alone (16 to 11), but not once the bonus counts
+        let analysis = analyze("en", "zacrets", &lex, &all, &values, &['A', 'E'], &balance);
+        assert_eq!(analysis.best_word.as_deref(), Some("CRATES"));
+        assert_eq!(analysis.best_score, 11 + length_bonus(6, 7));
+    }
+
+    #[test]
+    fn test_analyze_edge_cases() {
+        let lex = Lexicon::from_texts(&["AT", "CAT", "TA"]);
+        let values = crate::services::scoring::parse_values("A:1, C:3, T:1").unwrap();
+        let balance = Balance { min_vowels: 1, min_consonants: 1 };
+        let run = |rack: &str| analyze("en", rack, &lex, &lex.rack_positions(rack), &values, &['A'], &balance);
+
+        let none = run("qz");
+        assert_eq!((none.difficulty, none.best_word, none.best_score), (Difficulty::Unplayable, None, 0));
+        assert!(!none.full_rack_word);
+
+        // Blanks score nothing, so only the length bonus counts
+        let blanks = run("___");
+        assert_eq!((blanks.best_word.as_deref(), blanks.best_score), (Some("CAT"), length_bonus(3, 3)));
+        assert_eq!((blanks.vowels, blanks.consonants, blanks.blanks), (0, 0, 3));
+        assert!(blanks.full_rack_word && !blanks.balanced);
+
+        // AT and TA tie on score and length
+        let tie = run("ta");
+        assert_eq!((tie.best_word.as_deref(), tie.best_score), (Some("AT"), 2));
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Rejections are counted in hourly buckets so `since` queries stay cheap.
+const BUCKET_SECS: i64 = 3600;
+
+const DAY_SECS: i64 = 86_400;
+
+/// Per-word rejection history: bucket start (unix seconds) -> attempts
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct WordHistory {
+    buckets: BTreeMap<i64, u64>,
+}
+
+impl WordHistory {
+    fn count_since(&self, since: i64) -> u64 {
+        let start = since - since.rem_euclid(BUCKET_SECS);
+        self.buckets.range(start..).map(|(_, &n)| n).sum()
+    }
+
+    fn last_seen(&self) -> Option<i64> {
+        self.buckets.keys().next_back().copied()
+    }
+
+    fn total(&self) -> u64 {
+        self.buckets.values().sum()
+    }
+
+    /// Eviction order: least rejected first, the stalest of those first
+    fn rank(&self, word: &str) -> (u64, i64, String) {
+        (self.total(), self.last_seen().unwrap_or(i64::MIN), word.to_string())
+    }
+}
+
+/// One language's rejected words, with their eviction order kept alongside so a full
+/// language finds the word to evict without scanning every entry
+#[derive(Default)]
+struct LangRejections {
+    words: HashMap<String, WordHistory>,
+    ranks: BTreeSet<(u64, i64, String)>,
+}
+
+impl LangRejections {
+    /// Index restored or pruned histories, evicting down to `max_words`
+    fn from_words(words: HashMap<String, WordHistory>, max_words: usize) -> Self {
+        let ranks = words.iter().map(|(word, history)| history.rank(word)).collect();
+        let mut lang = LangRejections { words, ranks };
+        while lang.words.len() > max_words {
+            lang.evict();
+        }
+        lang
+    }
+
+    fn evict(&mut self) -> bool {
+        match self.ranks.pop_first() {
+            Some((_, _, word)) => self.words.remove(&word).is_some(),
+            None => false,
+        }
+    }
+
+    fn add(&mut self, word: &str, bucket: i64) {
+        let history = self.words.entry(word.to_string()).or_default();
+        self.ranks.remove(&history.rank(word));
+        *history.buckets.entry(bucket).or_insert(0) += 1;
+        self.ranks.insert(history.rank(word));
+    }
+}
+
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct RejectedWord {
+    pub word: String,
+    pub count: u64,
+    /// Start of the most recent hour bucket with a rejection (unix seconds)
+    pub last_seen: i64,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct RejectionReport {
+    pub language: String,
+    pub since: i64,
+    pub total: u64,
+    pub missing: Vec<RejectedWord>,
+    pub deleted: Vec<RejectedWord>,
+}
+
+/// Rolling per-language counts of words rejected by the validation endpoints.
+/// Buckets older than the retention window are pruned on every save. Anything a
+/// client sends can end up here, so only plausible words are kept, and at most
+/// `max_words` of them per language.
+pub struct RejectionLog {
+    langs: Mutex<HashMap<String, LangRejections>>,
+    path: Option<String>,
+    retention_secs: i64,
+    max_word_len: usize,
+    max_words: usize,
+}
+
+impl RejectionLog {
+    pub fn new(path: Option<String>, retention_days: i64, max_word_len: usize, max_words: usize) -> Self {
+        RejectionLog {
+            langs: Mutex::new(HashMap::new()),
+            path,
+            retention_secs: retention_days * DAY_SECS,
+            max_word_len,
+            max_words,
+        }
+    }
+
+    /// Create a log, restoring previously persisted counts if a path is configured.
+    /// A file saved under a larger cap is trimmed to `max_words` per language.
+    pub fn open(path: Option<String>, retention_days: i64, max_word_len: usize, max_words: usize) -> Self {
+        let log = Self::new(path, retention_days, max_word_len, max_words);
+        if let Some(path) = &log.path {
+            match fs::read_to_string(path) {
+                Ok(json) => match serde_json::from_str::<HashMap<String, HashMap<String, WordHistory>>>(&json) {
+                    Ok(langs) => {
+                        *log.langs.lock().unwrap() = langs
+                            .into_iter()
+                            .map(|(lang, words)| (lang, LangRejections::from_words(words, max_words)))
+                            .collect();
+                        info!("Restored rejection counts from {}", path);
+                    }
+                    Err(e) => warn!("Ignoring unreadable rejection log {}: {}", path, e),
+                },
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => warn!("Failed to read rejection log {}: {}", path, e),
+            }
+        }
+        log
+    }
+
+    /// Count a rejection. Strings that could never be words (too long for a rack, or
+    /// containing anything but letters) are ignored. A new word arriving when the
+    /// language is full evicts the least-rejected word, the stalest of those first.
+    pub fn record(&self, lang: &str, word: &str) {
+        self.record_at(lang, word, Utc::now().timestamp());
+    }
+
+    fn record_at(&self, lang: &str, word: &str, now: i64) {
+        if word.is_empty() || word.chars().count() > self.max_word_len || !word.chars().all(char::is_alphabetic) {
+            return;
+        }
+
+        let bucket = now - now.rem_euclid(BUCKET_SECS);
+        let mut langs = self.langs.lock().unwrap();
+        let rejections = langs.entry(lang.to_string()).or_default();
+        if !rejections.words.contains_key(word) && rejections.words.len() >= self.max_words && !rejections.evict() {
+            return;
+        }
+        rejections.add(word, bucket);
+    }
+
+    /// Most-attempted rejected words since `since`, split into words missing from the
+    /// lexicon and words deliberately removed by `deletions.txt`.
+    pub fn report(&self, lang: &str, since: i64, limit: usize, deletions: &HashSet<String>) -> RejectionReport {
+        let langs = self.langs.lock().unwrap();
+        let mut missing = Vec::new();
+        let mut deleted = Vec::new();
+        let mut total = 0;
+
+        if let Some(rejections) = langs.get(lang) {
+            for (word, history) in &rejections.words {
+                let count = history.count_since(since);
+                if count == 0 {
+                    continue;
+                }
+                total += count;
+                let entry = RejectedWord {
+                    word: word.clone(),
+                    count,
+                    last_seen: history.last_seen().unwrap_or(since),
+                };
+                if deletions.contains(word) {
+                    deleted.push(entry);
+                } else {
+                    missing.push(entry);
+                }
+            }
+        }
+
+        for list in [&mut missing, &mut deleted] {
+            list.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+            list.truncate(limit);
+        }
+
+        RejectionReport {
+            language: lang.to_string(),
+            since,
+            total,
+            missing,
+            deleted,
+        }
+    }
+
+    /// Drop buckets outside the retention window, then persist if a path is configured
+    pub fn save(&self) -> io::Result<()> {
+        self.save_at(Utc::now().timestamp())
+    }
+
+    fn save_at(&self, now: i64) -> io::Result<()> {
+        let cutoff = now - self.retention_secs;
+        let mut langs = self.langs.lock().unwrap();
+        for rejections in langs.values_mut() {
+            let mut words = std::mem::take(&mut rejections.words);
+            words.retain(|_, history| {
+                history.buckets.retain(|&bucket, _| bucket >= cutoff);
+                !history.buckets.is_empty()
+            });
+            // Pruning changes totals, so the eviction order is rebuilt
+            *rejections = LangRejections::from_words(words, self.max_words);
+        }
+
+        let Some(path) = &self.path else { return Ok(()) };
+        let persisted: HashMap<&String, &HashMap<String, WordHistory>> =
+            langs.iter().map(|(lang, rejections)| (lang, &rejections.words)).collect();
+        let json = serde_json::to_string(&persisted)?;
+        drop(langs);
+
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_800_000_000;
+
+    fn words(report: &[RejectedWord]) -> Vec<(&str, u64)> {
+        report.iter().map(|r| (r.word.as_str(), r.count)).collect()
+    }
+
+    #[test]
+    fn test_record_and_report() {
+        let log = RejectionLog::new(None, 30, 7, 100);
+        for word in ["QWERT", "QWERT", "ZORP", "GONE", "TOOLONGWORD", "AB1", "A B", ""] {
+            log.record_at("en", word, NOW);
+        }
+        log.record_at("en", "ZORP", NOW - 2 * DAY_SECS);
+
+        let deletions = HashSet::from(["GONE".to_string()]);
+        let report = log.report("en", 0, 10, &deletions);
+        assert_eq!(report.total, 5);
+        assert_eq!(words(&report.missing), vec![("QWERT", 2), ("ZORP", 2)]);
+        assert_eq!(words(&report.deleted), vec![("GONE", 1)]);
+
+        let recent = log.report("en", NOW - DAY_SECS, 1, &deletions);
+        assert_eq!(words(&recent.missing), vec![("QWERT", 2)]);
+        assert_eq!(log.report("fr", 0, 10, &deletions).total, 0);
+    }
+
+    #[test]
+    fn test_cap_evicts_least_rejected() {
+        let log = RejectionLog::new(None, 30, 7, 2);
+        log.record_at("en", "AAA", NOW);
+        log.record_at("en", "AAA", NOW);
+        log.record_at("en", "BBB", NOW - BUCKET_SECS);
+        log.record_at("en", "CCC", NOW);
+        log.record_at("en", "DDD", NOW);
+
+        let report = log.report("en", 0, 10, &HashSet::new());
+        assert_eq!(words(&report.missing), vec![("AAA", 2), ("DDD", 1)]);
+    }
+
+    #[test]
+    fn test_prune_and_persist() {
+        let path = std::env::temp_dir().join(format!("wordd-rejections-{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let log = RejectionLog::new(Some(path.clone()), 1, 7, 100);
+        log.record_at("en", "OLD", NOW - 2 * DAY_SECS);
+        log.record_at("en", "NEW", NOW);
+        log.save_at(NOW).unwrap();
+
+        let restored = RejectionLog::open(Some(path.clone()), 1, 7, 100);
+        assert_eq!(words(&restored.report("en", 0, 10, &HashSet::new()).missing), vec![("NEW", 1)]);
+
+        // Restoring under a smaller cap keeps only the most-rejected words
+        log.record_at("en", "NEW", NOW);
+        log.record_at("en", "ONCE", NOW);
+        log.save_at(NOW).unwrap();
+        let capped = RejectionLog::open(Some(path.clone()), 1, 7, 1);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(words(&capped.report("en", 0, 10, &HashSet::new()).missing), vec![("NEW", 2)]);
+        capped.record_at("en", "LATE", NOW);
+        assert_eq!(words(&capped.report("en", 0, 10, &HashSet::new()).missing), vec![("LATE", 1)]);
+    }
+}
@@ -0,0 +1,148 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::services::lexicon::Lexicon;
+use crate::services::rack_analysis::RackPlay;
+use crate::services::scoring::{best_play, LetterValues, RackTiles};
+
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ScoredWord {
+    pub word: String,
+    /// Letter score plus length bonus
+    pub score: u32,
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct PlayerReport {
+    pub player: String,
+    pub word: String,
+    /// False if the word isn't in the lexicon or can't be formed from the rack
+    pub valid: bool,
+    pub score: u32,
+    pub best_word: Option<String>,
+    pub best_score: u32,
+    /// Points left on the table compared with the best word
+    pub missed: u32,
+    /// Share of playable words (0 to 100) that score less than this play
+    pub percentile: f64,
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct RoundAnalysis {
+    pub language: String,
+    pub rack: String,
+    pub playable_words: usize,
+    pub players: Vec<PlayerReport>,
+    /// Highest-scoring words, best first
+    pub top_words: Vec<ScoredWord>,
+    /// Highest-scoring word that uses every tile, if any
+    pub full_rack_word: Option<RackPlay>,
+}
+
+/// Score every word the rack can form and compare each player's play against them
+pub fn analyze_round(
+    lang: &str,
+    rack: &str,
+    words: &Lexicon,
+    matches: &[u32],
+    values: &LetterValues,
+    plays: &[(String, String)],
+    top: usize,
+) -> RoundAnalysis {
+    let tiles = RackTiles::new(rack);
+    let score = |word: &str| best_play(&tiles, word, values);
+
+    // Best first; ties longest first, then alphabetical, as the positions already are
+    let mut scored: Vec<ScoredWord> = matches
+        .iter()
+        .map(|&pos| {
+            let word = words.text(pos as usize);
+            ScoredWord { word: word.to_string(), score: score(word) }
+        })
+        .collect();
+    scored.sort_by_key(|s| std::cmp::Reverse(s.score));
+
+    let best = scored.first().cloned();
+    let full_rack_word = scored.iter().find_map(|s| {
+        let play = tiles.assign(&s.word)?;
+        play.leftover.is_empty().then(|| RackPlay { word: s.word.clone(), blanks: play.blanks })
+    });
+
+    let players = plays
+        .iter()
+        .map(|(player, word)| {
+            let word = word.to_uppercase();
+            let valid = words.contains(&word) && tiles.assign(&word).is_some();
+            let played = if valid { score(&word) } else { 0 };
+            // Scores are sorted descending, so everything past the last word scoring at
+            // least as much scores less
+            let beaten = scored.len() - scored.partition_point(|s| s.score >= played);
+            let best_score = best.as_ref().map(|b| b.score).unwrap_or(0);
+            PlayerReport {
+                player: player.clone(),
+                word,
+                valid,
+                score: played,
+                best_word: best.as_ref().map(|b| b.word.clone()),
+                best_score,
+                missed: best_score.saturating_sub(played),
+                percentile: if scored.is_empty() { 0.0 } else { (beaten * 1000 / scored.len()) as f64 / 10.0 },
+            }
+        })
+        .collect();
+
+    RoundAnalysis {
+        language: lang.to_string(),
+        rack: rack.to_uppercase(),
+        playable_words: scored.len(),
+        players,
+        top_words: scored.into_iter().take(top).collect(),
+        full_rack_word,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::scoring::parse_values;
+
+    #[test]
+    fn test_players_are_ranked_against_every_playable_word() {
+        let texts = ["AT", "CAT", "CATS", "TA"];
+        let lex = Lexicon::from_texts(&texts);
+        let matches = lex.rack_positions("CATS");
+        let values = parse_values("C:3,A:1,T:1,S:1").unwrap();
+        let plays = vec![("ann".to_string(), "cat".to_string()), ("bob".to_string(), "dog".to_string())];
+
+        let report = analyze_round("en", "cats", &lex, &matches, &values, &plays, 2);
+        // CATS: 6 + length bonus 5 * 2^(4 - 3)
+        assert_eq!(report.top_words[0].score, 16);
+        assert_eq!(report.full_rack_word.as_ref().map(|p| p.word.as_str()), Some("CATS"));
+        let ann = &report.players[0];
+        assert_eq!((ann.score, ann.missed, ann.percentile), (5, 11, 50.0));
+        assert!(!report.players[1].valid);
+    }
+
+    #[test]
+    fn test_round_edge_cases() {
+        let lex = Lexicon::from_texts(&["ACT", "AT", "CAT", "TA"]);
+        let values = parse_values("A:1,C:1,T:1").unwrap();
+        let plays = vec![("ann".to_string(), "ta".to_string()), ("bob".to_string(), "act".to_string())];
+
+        let empty = analyze_round("en", "qz", &lex, &[], &values, &plays, 5);
+        assert!(empty.top_words.is_empty() && empty.full_rack_word.is_none());
+        let ann = &empty.players[0];
+        assert_eq!((ann.valid, ann.best_word.as_deref(), ann.missed, ann.percentile), (false, None, 0, 0.0));
+
+        // Equal scores keep solver order: longest first, then alphabetical
+        let mut matches = lex.rack_positions("cat");
+        crate::services::solver::sort_matches(&lex, &mut matches);
+        let report = analyze_round("en", "cat", &lex, &matches, &values, &plays, 5);
+        let top: Vec<&str> = report.top_words.iter().map(|s| s.word.as_str()).collect();
+        assert_eq!(top, vec!["ACT", "CAT", "AT", "TA"]);
+        assert_eq!(report.full_rack_word.map(|p| p.word), Some("ACT".to_string()));
+        let (ann, bob) = (&report.players[0], &report.players[1]);
+        assert_eq!((ann.score, ann.percentile), (2, 0.0));
+        assert_eq!((bob.score, bob.missed, bob.percentile), (13, 0, 50.0));
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+/// Per-letter tile values. Blanks always score 0.
+pub type LetterValues = HashMap<char, u32>;
+
+/// Value of a unicorn tile, as in the backend's `Scorer`
+const UNICORN_VALUE: u32 = 10;
+
+/// The backend's default values: rarer tiles in the bag are worth more, and unicorns
+/// are worth 10. Games add a daily bonus letter on top, so callers that know the
+/// game's values should pass them instead.
+pub fn default_values(tile_bag: &HashMap<char, usize>, unicorns: &[char]) -> LetterValues {
+    let mut values: LetterValues = tile_bag
+        .iter()
+        .filter(|(&c, _)| c != '_')
+        .map(|(&c, &count)| {
+            let value = match count {
+                10.. => 1,
+                6.. => 2,
+                4.. => 3,
+                2.. => 4,
+                _ => 5,
+            };
+            (c, value)
+        })
+        .collect();
+    for &c in unicorns {
+        values.insert(c, UNICORN_VALUE);
+    }
+    values
+}
+
+/// Parse `A:1,B:3,...`; letters left out are worth 0
+pub fn parse_values(spec: &str) -> Result<LetterValues, String> {
+    spec.split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| {
+            let (letter, value) = pair
+                .split_once(':')
+                .ok_or_else(|| format!("Expected LETTER:VALUE, got '{}'", pair))?;
+            let mut chars = letter.trim().chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return Err(format!("Expected a single letter, got '{}'", letter));
+            };
+            let value = value.trim().parse::<u32>().map_err(|_| format!("Invalid value for '{}': '{}'", c, value))?;
+            Ok((c.to_uppercase().next().unwrap_or(c), value))
+        })
+        .collect()
+}
+
+/// Tile counts in a rack: letters uppercased, blanks counted separately
+pub struct RackTiles {
+    letters: HashMap<char, usize>,
+    pub blanks: usize,
+}
+
+impl RackTiles {
+    pub fn new(rack: &str) -> Self {
+        let mut letters = HashMap::new();
+        let mut blanks = 0;
+        for c in rack.to_uppercase().chars() {
+            if c == '_' {
+                blanks += 1;
+            } else if c.is_alphabetic() {
+                *letters.entry(c).or_insert(0) += 1;
+            }
+        }
+        RackTiles { letters, blanks }
+    }
+
+    /// Every tile, blanks included
+    pub fn len(&self) -> usize {
+        self.letters.values().sum::<usize>() + self.blanks
+    }
+
+    pub fn letters(&self) -> impl Iterator<Item = (char, usize)> + '_ {
+        self.letters.iter().map(|(&c, &n)| (c, n))
+    }
+
+    /// Best score for a word formable from the rack: real tiles cover as many of its
+    /// letters as they can, and only the shortfall is played with (worthless) blanks
+    pub fn score(&self, word: &str, values: &LetterValues) -> u32 {
+        let mut used: HashMap<char, usize> = HashMap::new();
+        word.chars()
+            .filter(|c| c.is_alphabetic())
+            .map(|c| {
+                let n = used.entry(c).or_insert(0);
+                *n += 1;
+                if *n <= self.letters.get(&c).copied().unwrap_or(0) {
+                    values.get(&c).copied().unwrap_or(0)
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+}
+
+/// How a word is played from a rack
+pub struct Play {
+    /// Letters the blanks stand for, in word order
+    pub blanks: Vec<char>,
+    /// Tiles left on the rack, sorted, with `_` for unused blanks
+    pub leftover: Vec<char>,
+}
+
+impl RackTiles {
+    /// Play `word` from the rack, real tiles first, or None if it doesn't fit
+    pub fn assign(&self, word: &str) -> Option<Play> {
+        let mut left = self.letters.clone();
+        let mut blanks_left = self.blanks;
+        let mut blanks = Vec::new();
+        for c in word.chars().filter(|c| c.is_alphabetic()) {
+            match left.get_mut(&c) {
+                Some(n) if *n > 0 => *n -= 1,
+                _ if blanks_left > 0 => {
+                    blanks_left -= 1;
+                    blanks.push(c);
+                }
+                _ => return None,
+            }
+        }
+        let mut leftover: Vec<char> = left.into_iter().flat_map(|(c, n)| std::iter::repeat_n(c, n)).collect();
+        leftover.sort_unstable();
+        leftover.extend(std::iter::repeat_n('_', blanks_left));
+        Some(Play { blanks, leftover })
+    }
+}
+
+/// Shortest word that earns a length bonus, as `Scorer::get_min_bonus_len`
+pub fn min_bonus_len(rack_size: usize) -> usize {
+    rack_size / 2 + 1
+}
+
+/// Bonus for a word of `len` letters, as `Scorer::get_length_bonus`: 5 points doubling
+/// with each letter past the minimum bonus length
+pub fn length_bonus(len: usize, rack_size: usize) -> u32 {
+    match len.checked_sub(min_bonus_len(rack_size)) {
+        Some(extra) if extra >= 1 => 5u32.saturating_mul(1 << extra.min(31)),
+        _ => 0,
+    }
+}
+
+/// What `word` scores played from the rack: its best letter score plus the length bonus
+pub fn best_play(tiles: &RackTiles, word: &str, values: &LetterValues) -> u32 {
+    let len = word.chars().filter(|c| c.is_alphabetic()).count();
+    tiles.score(word, values) + length_bonus(len, tiles.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_plays_blanks_for_the_shortfall_only() {
+        let values = parse_values("A:1, t:2, C:5").unwrap();
+        let rack = RackTiles::new("cat_");
+        assert_eq!(rack.len(), 4);
+        assert_eq!(rack.score("CAT", &values), 8);
+        // The second T must be the blank
+        assert_eq!(rack.score("TACT", &values), 8);
+        assert!(parse_values("AB:1").is_err());
+
+        let play = rack.assign("TACT").unwrap();
+        assert_eq!((play.blanks, play.leftover), (vec!['T'], vec![]));
+        assert_eq!(rack.assign("AT").unwrap().leftover, vec!['C', '_']);
+        assert!(rack.assign("TATTY").is_none());
+
+        assert_eq!((length_bonus(4, 7), length_bonus(5, 7), length_bonus(7, 7)), (0, 10, 40));
+
+        let full = RackTiles::new("CATSEAT");
+        assert_eq!(best_play(&full, "CAT", &values), 8);
+        assert_eq!(best_play(&full, "CASTE", &values), 8 + length_bonus(5, 7));
+    }
+
+    #[test]
+    fn test_parse_values_rejects_malformed_specs() {
+        assert_eq!(parse_values(" a : 2 ,, ").unwrap(), LetterValues::from([('A', 2)]));
+        assert!(parse_values("").unwrap().is_empty());
+        for spec in ["A", "A:", "A:x", "A:-1", ":1", "A:1,B"] {
+            assert!(parse_values(spec).is_err(), "spec {}", spec);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::cmp::Reverse;
+
+use crate::services::lexicon::Lexicon;
+use crate::services::budget::Budget;
+use crate::services::generator::ScanPage;
+
+/// Put a rack's matching positions in solver order: longest first, then alphabetically.
+/// Positions are alphabetical already, so they break ties between equal lengths.
+pub fn sort_matches(words: &Lexicon, matches: &mut [u32]) {
+    matches.sort_by_cached_key(|&pos| (Reverse(words.text(pos as usize).chars().count()), pos));
+}
+
+/// One page of solver results from matches in solver order, resuming at index `start`
+/// and stopping at the budget's result limit, so the first page holds the longest words
+pub fn page(words: &Lexicon, matches: &[u32], start: usize, budget: &Budget) -> ScanPage {
+    let rest = matches.get(start..).unwrap_or_default();
+    let taken = rest.len().min(budget.max_results);
+    ScanPage {
+        words: rest[..taken].iter().map(|&pos| words.text(pos as usize).to_string()).collect(),
+        next_position: (taken < rest.len()).then_some(start + taken),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::services::budget::QueryLimits;
+
+    #[test]
+    fn test_pages_run_longest_first() {
+        let mut texts = ["AT", "ACT", "CAT", "CATS", "SCAT", "TA", "CAST", "ACTS"];
+        texts.sort_unstable();
+        let lex = Lexicon::from_texts(&texts);
+        let mut matches: Vec<u32> = (0..lex.len() as u32).collect();
+        sort_matches(&lex, &mut matches);
+
+        let limits = QueryLimits::new(3, Duration::from_secs(1), 7);
+        let first = page(&lex, &matches, 0, &limits.budget(None, None));
+        assert_eq!(first.words, ["ACTS", "CAST", "CATS"]);
+        assert_eq!(first.next_position, Some(3));
+
+        let second = page(&lex, &matches, 3, &limits.budget(None, None));
+        assert_eq!(second.words, ["SCAT", "ACT", "CAT"]);
+        let last = page(&lex, &matches, 6, &limits.budget(None, None));
+        assert_eq!(last.words, ["AT", "TA"]);
+        assert_eq!(last.next_position, None);
+        assert!(page(&lex, &matches, 99, &limits.budget(None, None)).words.is_empty());
+    }
+}
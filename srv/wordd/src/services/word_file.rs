@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use encoding_rs::Encoding;
+use flate2::read::MultiGzDecoder;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// A line that could not be decoded cleanly (1-based line number)
+#[derive(Debug, Clone)]
+pub struct DecodeIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Decoded contents of a word file, with how it was stored
+pub struct WordFile {
+    pub path: String,
+    pub encoding: &'static str,
+    pub compression: Option<&'static str>,
+    /// (line number, trimmed text) for every non-empty, non-comment line that decoded cleanly
+    pub lines: Vec<(usize, String)>,
+    pub issues: Vec<DecodeIssue>,
+}
+
+/// Pick the first existing variant of a word file: plain, `.gz`, or `.zst`.
+/// Falls back to the plain path so "not found" errors name the expected file.
+pub fn resolve_path(path: &str) -> String {
+    ["", ".gz", ".zst"]
+        .iter()
+        .map(|ext| format!("{}{}", path, ext))
+        .find(|candidate| Path::new(candidate).is_file())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Undo gzip or zstd compression, detected by magic bytes rather than file extension
+fn decompress(raw: Vec<u8>) -> io::Result<(Vec<u8>, Option<&'static str>)> {
+    if raw.starts_with(GZIP_MAGIC) {
+        let mut out = Vec::new();
+        MultiGzDecoder::new(raw.as_slice()).read_to_end(&mut out)?;
+        Ok((out, Some("gzip")))
+    } else if raw.starts_with(ZSTD_MAGIC) {
+        Ok((zstd::decode_all(raw.as_slice())?, Some("zstd")))
+    } else {
+        Ok((raw, None))
+    }
+}
+
+/// A first-line comment like `# encoding: latin-1` (or `# charset=...`) declares the encoding
+fn declared_encoding(bytes: &[u8]) -> Option<&'static Encoding> {
+    let end = bytes.iter().position(|&b| b == b'\n').unwrap_or(bytes.len());
+    let first = String::from_utf8_lossy(&bytes[..end]);
+    let comment = first.trim().strip_prefix('#')?.trim().to_ascii_lowercase();
+    let label = ["encoding", "charset", "coding"]
+        .iter()
+        .find_map(|key| comment.strip_prefix(key))?
+        .trim_start_matches([':', '=', ' '])
+        .trim();
+    Encoding::for_label(label.as_bytes())
+        .or_else(|| Encoding::for_label(label.replace('-', "").as_bytes()))
+}
+
+/// Decode raw bytes: a BOM (UTF-8/UTF-16) wins, then a declared encoding, then UTF-8
+pub fn decode(bytes: &[u8]) -> (&'static Encoding, String, Vec<DecodeIssue>) {
+    let (encoding, body) = match Encoding::for_bom(bytes) {
+        Some((enc, bom_len)) => (enc, &bytes[bom_len..]),
+        None => (declared_encoding(bytes).unwrap_or(encoding_rs::UTF_8), bytes),
+    };
+
+    let (text, had_errors) = encoding.decode_without_bom_handling(body);
+    let mut issues = Vec::new();
+    if had_errors {
+        for (idx, line) in text.lines().enumerate() {
+            if line.contains(char::REPLACEMENT_CHARACTER) {
+                issues.push(DecodeIssue {
+                    line: idx + 1,
+                    message: format!("invalid {} byte sequence", encoding.name()),
+                });
+            }
+        }
+    }
+
+    (encoding, text.into_owned(), issues)
+}
+
+/// Read a word file, transparently handling compression and text encoding.
+/// Lines with decode errors are reported in `issues` and left out of `lines`.
+pub fn read_word_file(path: &str) -> io::Result<WordFile> {
+    let path = resolve_path(path);
+    let (bytes, compression) = decompress(fs::read(&path)?)?;
+    let (encoding, text, issues) = decode(&bytes);
+
+    let bad_lines: HashSet<usize> = issues.iter().map(|i| i.line).collect();
+    let lines = text
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(n, line)| !line.is_empty() && !line.starts_with('#') && !bad_lines.contains(n))
+        .map(|(n, line)| (n, line.to_string()))
+        .collect();
+
+    Ok(WordFile {
+        path,
+        encoding: encoding.name(),
+        compression,
+        lines,
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_decode_utf16_bom() {
+        let mut bytes = vec![0xff, 0xfe];
+        for unit in "été\r\nmer\r\n".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        let (encoding, text, issues) = decode(&bytes);
+        assert_eq!(encoding, encoding_rs::UTF_16LE);
+        assert_eq!(text.lines().map(str::trim).collect::<Vec<_>>(), vec!["été", "mer"]);
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_decode_declared_latin1() {
+        let bytes = b"# encoding: latin-1\ncaf\xe9\n";
+        let (_, text, issues) = decode(bytes);
+        assert_eq!(text.lines().nth(1), Some("café"));
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_decode_reports_bad_utf8_lines() {
+        let bytes = b"good\nba\xffd\nfine\n";
+        let (_, _, issues) = decode(bytes);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 2);
+    }
+
+    #[test]
+    fn test_decompress_gzip() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"word\n").unwrap();
+        let (bytes, compression) = decompress(encoder.finish().unwrap()).unwrap();
+        assert_eq!(bytes, b"word\n");
+        assert_eq!(compression, Some("gzip"));
+    }
+
+    #[test]
+    fn test_decompress_zstd() {
+        let compressed = zstd::encode_all("mot\néléphant\n".as_bytes(), 3).unwrap();
+        let (bytes, compression) = decompress(compressed).unwrap();
+        assert_eq!(bytes, "mot\néléphant\n".as_bytes());
+        assert_eq!(compression, Some("zstd"));
+
+        let (plain, compression) = decompress(b"word\n".to_vec()).unwrap();
+        assert_eq!((plain.as_slice(), compression), (b"word\n".as_slice(), None));
+    }
+}
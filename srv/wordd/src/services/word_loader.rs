@@ -1,44 +1,88 @@
 use std::collections::{HashSet, HashMap};
-use std::fs::File;
-use std::io::{self, BufRead};
-use log::{info, warn};
+use std::io;
+use fst::Set;
+use log::{debug, info, warn};
+use serde::Serialize;
+use utoipa::ToSchema;
 
 use crate::models::Word;
+use crate::services::word_file::{read_word_file, WordFile};
 use crate::utils::compute_signature;
 
-/// Load words from a plain text file (one word per line)
+/// Maximum number of per-line decode problems logged for a single file
+const MAX_LOGGED_ISSUES: usize = 10;
+
+/// Load words from a word file (one word per line). Compression and encoding are
+/// detected by `word_file`; undecodable lines are logged with their line numbers and skipped.
 pub fn load_words(file_path: &str, max_len: usize) -> io::Result<HashSet<String>> {
-    let file = File::open(file_path)?;
-    let reader = io::BufReader::new(file);
-    
-    let mut words = HashSet::new();
-    for line in reader.lines() {
-        let line = line?;
-        let word = line.trim();
-        if word.starts_with('#') || word.is_empty() { continue; }
-        if word.chars().count() <= max_len {
-            words.insert(word.to_uppercase());
+    let file = read_word_file(file_path)?;
+    debug!("Read {} lines from {} ({}, {})", file.lines.len(), file.path, file.encoding,
+           file.compression.unwrap_or("uncompressed"));
+    log_decode_issues(&file);
+
+    let words = file.lines.into_iter()
+        .map(|(_, word)| word)
+        .filter(|word| word.chars().count() <= max_len)
+        .map(|word| word.to_uppercase())
+        .collect();
+
+    Ok(words)
+}
+
+/// Load an optional curation file; a missing file is simply empty
+fn load_optional_words(file_path: &str, max_len: usize) -> HashSet<String> {
+    load_words(file_path, max_len).unwrap_or_else(|e| {
+        if e.kind() != io::ErrorKind::NotFound {
+            warn!("Failed to load {}: {}", file_path, e);
         }
+        HashSet::new()
+    })
+}
+
+fn log_decode_issues(file: &WordFile) {
+    for issue in file.issues.iter().take(MAX_LOGGED_ISSUES) {
+        warn!("{}:{}: {}", file.path, issue.line, issue.message);
     }
-    
-    Ok(words)
+    if file.issues.len() > MAX_LOGGED_ISSUES {
+        warn!("{}: {} more lines could not be decoded", file.path, file.issues.len() - MAX_LOGGED_ISSUES);
+    }
+}
+
+/// Minimum share of lexicon words a letter must appear in to survive the frequency filter
+pub const MIN_LETTER_FREQUENCY: f64 = 0.01;
+
+/// Side inputs of the load pipeline, kept so individual words can be explained later
+#[derive(Clone, Default)]
+pub struct PipelineInputs {
+    /// Every word in the base lexicon file as loaded, of any length
+    pub base_lexicon: Set<Vec<u8>>,
+    pub rare_letters: HashSet<char>,
+    pub insertions: HashSet<String>,
+    pub deletions: HashSet<String>,
+}
+
+/// Final word list for a language, plus the pipeline inputs that shaped it
+pub struct LoadedWords {
+    pub words: Vec<Word>,
+    pub inputs: PipelineInputs,
 }
 
 /// Load and merge lexicon, insertions, and deletions for a language.
 /// Pipeline: lexicon → 1% freq filter → insertions (bypass filter) → deletions
-pub fn load_filtered_words(base_dir: &str, lang: &str, max_len: usize) -> Vec<Word> {
-    let lang_dir = format!("{}/words/{}", base_dir, lang);
-
-    let valid_path    = format!("{}/lexicon.txt", lang_dir);
-    let custom_path   = format!("{}/insertions.txt", lang_dir);
-    let censored_path = format!("{}/deletions.txt", lang_dir);
+pub fn load_filtered_words(base_dir: &str, lang: &str, max_len: usize) -> LoadedWords {
+    let valid_path  = lexicon_path(base_dir, lang);
+    let custom_path = insertions_path(base_dir, lang);
 
-    // 1. Load base lexicon
-    let base_set = load_words(&valid_path, max_len)
-        .unwrap_or_else(|_| {
-            warn!("Failed to load main lexicon for {} at {}.", lang, valid_path);
+    // 1. Load base lexicon, remembering all of it so `explain_word` needn't reread the file
+    let base_set = load_words(&valid_path, usize::MAX)
+        .unwrap_or_else(|e| {
+            warn!("Failed to load main lexicon for {} at {}: {}", lang, valid_path, e);
             HashSet::new()
         });
+    let mut sorted: Vec<&String> = base_set.iter().collect();
+    sorted.sort_unstable();
+    let base_lexicon = Set::from_iter(sorted).expect("sorted, deduplicated words");
+    let base_set: HashSet<String> = base_set.into_iter().filter(|word| word.chars().count() <= max_len).collect();
 
     info!("Base lexicon for {} (max_len {}): {} words", lang, max_len, base_set.len());
 
@@ -46,22 +90,25 @@ pub fn load_filtered_words(base_dir: &str, lang: &str, max_len: usize) -> Vec<Wo
     let base_words: Vec<Word> = base_set.into_iter()
         .map(|text| Word { signature: compute_signature(&text), len: text.len(), text })
         .collect();
-    let base_words = filter_by_min_letter_frequency(base_words, 0.01);
+    let rare = rare_letters(&base_words, MIN_LETTER_FREQUENCY);
+    let base_words = filter_by_min_letter_frequency(base_words, MIN_LETTER_FREQUENCY);
     info!("After letter frequency filter for {} (min 1%): {} words", lang, base_words.len());
 
     // Re-collect filtered base into a set so we can extend with insertions
     let mut word_set: HashSet<String> = base_words.into_iter().map(|w| w.text).collect();
 
     // 3. Merge insertions (hand-curated — bypass frequency filter)
-    if let Ok(custom) = load_words(&custom_path, max_len) {
+    let custom = load_optional_words(&custom_path, max_len);
+    if !custom.is_empty() {
         info!("Inserted {} words into {} lexicon.", custom.len(), lang);
-        word_set.extend(custom);
+        word_set.extend(custom.iter().cloned());
     }
 
     // 4. Apply deletions
-    if let Ok(censored) = load_words(&censored_path, max_len + 100) {
+    let censored = load_deletions(base_dir, lang);
+    if !censored.is_empty() {
         info!("Deleted {} words from {} lexicon.", censored.len(), lang);
-        for word in censored { word_set.remove(&word); }
+        for word in &censored { word_set.remove(word); }
     }
 
     // 5. Final sort
@@ -71,35 +118,186 @@ pub fn load_filtered_words(base_dir: &str, lang: &str, max_len: usize) -> Vec<Wo
     words.sort_by(|a, b| a.text.cmp(&b.text));
     info!("Total valid words for {} after all filters: {}", lang, words.len());
 
-    words
+    LoadedWords {
+        words,
+        inputs: PipelineInputs {
+            base_lexicon,
+            rare_letters: rare,
+            insertions: custom,
+            deletions: censored,
+        },
+    }
 }
 
-/// Remove letters (and the words containing them) that appear in fewer than `min_pct`
-/// of the word list. This eliminates rare accented chars and script outliers from the bag.
-pub fn filter_by_min_letter_frequency(mut words: Vec<Word>, min_pct: f64) -> Vec<Word> {
+pub fn lexicon_path(base_dir: &str, lang: &str) -> String {
+    format!("{}/words/{}/lexicon.txt", base_dir, lang)
+}
+
+pub fn insertions_path(base_dir: &str, lang: &str) -> String {
+    format!("{}/words/{}/insertions.txt", base_dir, lang)
+}
+
+pub fn deletions_path(base_dir: &str, lang: &str) -> String {
+    format!("{}/words/{}/deletions.txt", base_dir, lang)
+}
+
+pub fn offensive_path(base_dir: &str, lang: &str) -> String {
+    format!("{}/words/{}/offensive.txt", base_dir, lang)
+}
+
+/// Load the words that stay playable but are never picked for players, such as a
+/// daily target (empty if there is no offensive file)
+pub fn load_offensive(base_dir: &str, lang: &str) -> HashSet<String> {
+    load_optional_words(&offensive_path(base_dir, lang), usize::MAX)
+}
+
+/// Load the hand-curated deletions for a language (empty if there is no deletions file).
+/// Deletions are not length-limited so long censored words are still recognised.
+pub fn load_deletions(base_dir: &str, lang: &str) -> HashSet<String> {
+    let censored_path = deletions_path(base_dir, lang);
+    load_optional_words(&censored_path, usize::MAX)
+}
+
+/// Letters that appear in fewer than `min_pct` of the word list
+pub fn rare_letters(words: &[Word], min_pct: f64) -> HashSet<char> {
     let total = words.len();
     if total == 0 {
-        return words;
+        return HashSet::new();
     }
     let threshold = ((total as f64) * min_pct).ceil() as usize;
 
     // Count: for each letter, how many *distinct words* contain it
     let mut letter_word_count: HashMap<char, usize> = HashMap::new();
-    for word in &words {
+    for word in words {
         let letters: HashSet<char> = word.text.chars().collect();
         for ch in letters {
             *letter_word_count.entry(ch).or_insert(0) += 1;
         }
     }
 
-    // Valid letters are those meeting the threshold
-    let valid_letters: HashSet<char> = letter_word_count
+    letter_word_count
         .into_iter()
-        .filter(|(_, count)| *count >= threshold)
+        .filter(|(_, count)| *count < threshold)
         .map(|(ch, _)| ch)
-        .collect();
+        .collect()
+}
+
+/// Remove letters (and the words containing them) that appear in fewer than `min_pct`
+/// of the word list. This eliminates rare accented chars and script outliers from the bag.
+pub fn filter_by_min_letter_frequency(mut words: Vec<Word>, min_pct: f64) -> Vec<Word> {
+    let rare = rare_letters(&words, min_pct);
 
     // Keep only words whose every character is a valid letter
-    words.retain(|w| w.text.chars().all(|ch| valid_letters.contains(&ch)));
+    words.retain(|w| !w.text.chars().any(|ch| rare.contains(&ch)));
     words
 }
+
+/// Why a word ended up (or didn't end up) in the final word list
+#[derive(Serialize, Debug, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WordStatus {
+    Valid,
+    Inserted,
+    Deleted,
+    TooLong,
+    RareLetter,
+    NotInLexicon,
+}
+
+/// Step-by-step trace of a word through `load_filtered_words`
+#[derive(Serialize, Debug, ToSchema)]
+pub struct WordTrace {
+    pub word: String,
+    pub length: usize,
+    pub max_len: usize,
+    pub in_lexicon: bool,
+    pub exceeds_max_len: bool,
+    pub dropped_by_frequency_filter: bool,
+    pub rare_letters: Vec<char>,
+    pub in_insertions: bool,
+    pub in_deletions: bool,
+    pub status: WordStatus,
+}
+
+/// Trace a word through the load pipeline using the inputs recorded at load time, so
+/// the answer describes the data being served even if the files have changed since
+pub fn explain_word(max_len: usize, word: &str, inputs: &PipelineInputs) -> WordTrace {
+    let word = word.to_uppercase();
+    let length = word.chars().count();
+    let exceeds_max_len = length > max_len;
+
+    let in_lexicon = inputs.base_lexicon.contains(&word);
+
+    let mut rare: Vec<char> = word.chars().filter(|ch| inputs.rare_letters.contains(ch)).collect();
+    rare.sort_unstable();
+    rare.dedup();
+    let dropped_by_frequency_filter = in_lexicon && !exceeds_max_len && !rare.is_empty();
+
+    let in_insertions = inputs.insertions.contains(&word);
+    let in_deletions = inputs.deletions.contains(&word);
+
+    let status = if exceeds_max_len {
+        WordStatus::TooLong
+    } else if in_deletions && (in_lexicon || in_insertions) {
+        WordStatus::Deleted
+    } else if in_insertions {
+        WordStatus::Inserted
+    } else if !in_lexicon {
+        WordStatus::NotInLexicon
+    } else if dropped_by_frequency_filter {
+        WordStatus::RareLetter
+    } else {
+        WordStatus::Valid
+    };
+
+    WordTrace {
+        word,
+        length,
+        max_len,
+        in_lexicon,
+        exceeds_max_len,
+        dropped_by_frequency_filter,
+        rare_letters: rare,
+        in_insertions,
+        in_deletions,
+        status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> PipelineInputs {
+        let mut base = vec!["CAT", "DOG", "OLD", "QAJAQ", "STRENGTHS"];
+        base.sort_unstable();
+        PipelineInputs {
+            base_lexicon: Set::from_iter(base).unwrap(),
+            rare_letters: HashSet::from(['J']),
+            insertions: HashSet::from(["NEW".to_string(), "OLD".to_string(), "BAD".to_string()]),
+            deletions: HashSet::from(["DOG".to_string(), "BAD".to_string(), "NOPE".to_string()]),
+        }
+    }
+
+    #[test]
+    fn test_explain_status_table() {
+        let inputs = inputs();
+        let status = |word: &str| explain_word(7, word, &inputs).status;
+        assert_eq!(status("cat"), WordStatus::Valid);
+        assert_eq!(status("OLD"), WordStatus::Inserted);
+        assert_eq!(status("NEW"), WordStatus::Inserted);
+        assert_eq!(status("DOG"), WordStatus::Deleted);
+        assert_eq!(status("BAD"), WordStatus::Deleted);
+        // Deleting a word that was never in the list doesn't make it "deleted"
+        assert_eq!(status("NOPE"), WordStatus::NotInLexicon);
+        assert_eq!(status("ZZZ"), WordStatus::NotInLexicon);
+        assert_eq!(status("QAJAQ"), WordStatus::RareLetter);
+        assert_eq!(status("STRENGTHS"), WordStatus::TooLong);
+
+        let trace = explain_word(7, "qajaq", &inputs);
+        assert!(trace.in_lexicon && trace.dropped_by_frequency_filter);
+        assert_eq!(trace.rare_letters, vec!['J']);
+        let long = explain_word(7, "strengths", &inputs);
+        assert!(long.in_lexicon && !long.dropped_by_frequency_filter);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::services::lexicon::Lexicon;
+use crate::services::likelihood::Commonness;
+
+/// Letters in every target and guess
+pub const WORD_LENGTH: usize = 5;
+
+/// Guesses allowed before the answer is revealed
+pub const MAX_GUESSES: usize = 6;
+
+/// Every player's day turns over at midnight here, wherever they or the server are
+pub const DAY_ZONE: Tz = chrono_tz::America::New_York;
+
+/// How one guessed letter compares with the hidden word
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Feedback {
+    /// Right letter, right place
+    Correct,
+    /// In the word, but elsewhere
+    Present,
+    /// Not in the word, or every copy of it is already accounted for
+    Absent,
+}
+
+/// Positions of the words fit to be a daily target: the right length, letters only,
+/// and neither offensive nor obscure, since players have to be able to guess them
+pub fn target_pool(words: &Lexicon, commonness: &Commonness, offensive: &HashSet<String>) -> Vec<u32> {
+    (0..words.len())
+        .filter(|&pos| {
+            let word = words.text(pos);
+            word.chars().count() == WORD_LENGTH
+                && word.chars().all(char::is_alphabetic)
+                && !offensive.contains(word)
+                && !commonness.is_obscure(words, word)
+        })
+        .map(|pos| pos as u32)
+        .collect()
+}
+
+/// The game day an instant falls on
+pub fn game_day(now: DateTime<Utc>) -> NaiveDate {
+    now.with_timezone(&DAY_ZONE).date_naive()
+}
+
+/// The hidden word for a language on a day: the same for every player and every
+/// server, since it depends only on the language, the date, and the target pool
+pub fn daily_target(pool: &[u32], lang: &str, day: NaiveDate) -> Option<u32> {
+    if pool.is_empty() {
+        return None;
+    }
+    let digest = Sha256::digest(format!("{}:{}", lang, day).as_bytes());
+    let seed = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    Some(pool[(seed % pool.len() as u64) as usize])
+}
+
+/// Per-letter feedback for a guess of the target's length. Exact matches claim their
+/// letters first, then the rest are marked present left to right while unclaimed
+/// copies remain, so a doubled letter in the guess isn't credited twice.
+pub fn evaluate(target: &str, guess: &str) -> Vec<Feedback> {
+    let target: Vec<char> = target.chars().collect();
+    let guess: Vec<char> = guess.chars().collect();
+
+    let mut result = vec![Feedback::Absent; guess.len()];
+    let mut unclaimed: HashMap<char, usize> = HashMap::new();
+    for (i, &t) in target.iter().enumerate() {
+        if guess.get(i) == Some(&t) {
+            result[i] = Feedback::Correct;
+        } else {
+            *unclaimed.entry(t).or_insert(0) += 1;
+        }
+    }
+    for (i, g) in guess.iter().enumerate() {
+        if result[i] == Feedback::Correct {
+            continue;
+        }
+        if let Some(left) = unclaimed.get_mut(g).filter(|left| **left > 0) {
+            *left -= 1;
+            result[i] = Feedback::Present;
+        }
+    }
+    result
+}
+
+fn mac(key: &[u8], payload: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Identifies a game's hidden word without revealing it: keyed, since a plain hash of
+/// a five-letter word is quickly brute-forced
+pub fn target_digest(key: &[u8], target: &str) -> String {
+    hex(&mac(key, &format!("target:{}", target)).finalize().into_bytes()[..8])
+}
+
+/// A game in progress, carried by the client as a signed token so the server keeps no
+/// state. The signature stops a client from editing its guess history; replaying an
+/// older token of the same game is possible but gains nothing a fresh start wouldn't.
+/// `target` is the hidden word's [`target_digest`], so a game whose word changes under
+/// it (the lexicon was reloaded) is refused instead of scored against a different word.
+#[derive(Debug, PartialEq)]
+pub struct GameState {
+    pub lang: String,
+    pub day: NaiveDate,
+    pub target: String,
+    pub guesses: Vec<String>,
+}
+
+impl GameState {
+    fn payload(&self) -> String {
+        format!("{}:{}:{}:{}", self.lang, self.day, self.target, self.guesses.join(","))
+    }
+
+    /// `lang:day:target:guess,guess,...:signature`
+    pub fn sign(&self, key: &[u8]) -> String {
+        let payload = self.payload();
+        format!("{}:{}", payload, hex(&mac(key, &payload).finalize().into_bytes()))
+    }
+
+    /// The state in a token, if it was signed with `key` and hasn't been altered
+    pub fn verify(key: &[u8], token: &str) -> Option<Self> {
+        let (payload, signature) = token.rsplit_once(':')?;
+        let bytes = (0..signature.len())
+            .step_by(2)
+            .map(|i| signature.get(i..i + 2).and_then(|hex| u8::from_str_radix(hex, 16).ok()))
+            .collect::<Option<Vec<u8>>>()?;
+        mac(key, payload).verify_slice(&bytes).ok()?;
+
+        let mut parts = payload.splitn(4, ':');
+        let lang = parts.next()?.to_string();
+        let day = parts.next()?.parse().ok()?;
+        let target = parts.next()?.to_string();
+        let guesses = parts.next()?;
+        let guesses = if guesses.is_empty() { Vec::new() } else { guesses.split(',').map(str::to_string).collect() };
+        Some(GameState { lang, day, target, guesses })
+    }
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct WordleGame {
+    pub language: String,
+    /// Date the target belongs to, as YYYY-MM-DD; days turn over at midnight New York time
+    pub day: String,
+    pub length: usize,
+    pub max_guesses: usize,
+    /// Send back with the first guess
+    pub token: String,
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct WordleGuessResult {
+    pub language: String,
+    pub day: String,
+    pub guess: String,
+    /// One entry per letter of the guess
+    pub feedback: Vec<Feedback>,
+    pub solved: bool,
+    pub guesses_used: usize,
+    pub guesses_left: usize,
+    /// Revealed once the game is over
+    pub answer: Option<String>,
+    /// Send back with the next guess
+    pub token: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Feedback::*;
+
+    #[test]
+    fn test_repeated_letters() {
+        assert_eq!(evaluate("CRANE", "CRANE"), vec![Correct; 5]);
+        // Only one E in the target: the exact match claims it, the other E is absent
+        assert_eq!(evaluate("CRANE", "EERIE"), vec![Absent, Absent, Present, Absent, Correct]);
+        // Two L's in the guess, one in the target: only the first is credited
+        assert_eq!(evaluate("HELPS", "LLAMA"), vec![Present, Absent, Absent, Absent, Absent]);
+        assert_eq!(evaluate("ÉCRAN", "ÉCRIT"), vec![Correct, Correct, Correct, Absent, Absent]);
+    }
+
+    #[test]
+    fn test_token_round_trip_and_tampering() {
+        let state = GameState {
+            lang: "en".to_string(),
+            day: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+            target: target_digest(b"secret", "SLOTH"),
+            guesses: vec!["CRANE".to_string(), "SLOTH".to_string()],
+        };
+        let token = state.sign(b"secret");
+        assert_eq!(GameState::verify(b"secret", &token), Some(state));
+        assert!(GameState::verify(b"other", &token).is_none());
+        assert!(GameState::verify(b"secret", &token.replacen("CRANE,", "", 1)).is_none());
+
+        let fresh = GameState { lang: "fr".to_string(), day: NaiveDate::MIN, target: target_digest(b"k", "ÉCRAN"), guesses: Vec::new() };
+        assert_eq!(GameState::verify(b"k", &fresh.sign(b"k")).unwrap().guesses, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_target_digest() {
+        assert_eq!(target_digest(b"k", "CRANE"), target_digest(b"k", "CRANE"));
+        assert_ne!(target_digest(b"k", "CRANE"), target_digest(b"k", "SLOTH"));
+        assert_ne!(target_digest(b"k", "CRANE"), target_digest(b"other", "CRANE"));
+        assert!(!target_digest(b"k", "CRANE").contains(':'));
+    }
+
+    #[test]
+    fn test_days_turn_over_in_new_york() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        // Daylight time: UTC-4
+        assert_eq!(game_day(at("2026-10-19T03:59:59Z")), day(2026, 10, 18));
+        assert_eq!(game_day(at("2026-10-19T04:00:00Z")), day(2026, 10, 19));
+        // Standard time: UTC-5
+        assert_eq!(game_day(at("2026-12-01T04:59:59Z")), day(2026, 11, 30));
+        assert_eq!(game_day(at("2026-12-01T05:00:00Z")), day(2026, 12, 1));
+    }
+}